@@ -0,0 +1,296 @@
+use num_traits::Float;
+
+use crate::{DRY_AIR_GAS_CONSTANT, FEET_PER_METER, KELVIN_OFFSET, STANDARD_GRAVITY};
+
+/// Standard sea-level pressure used as the altimeter datum, in hPa.
+pub const STANDARD_PRESSURE_HPA: f64 = 1013.25;
+
+/// ISA sea-level temperature in Kelvin.
+const ISA_TEMPERATURE_K: f64 = 288.15;
+
+/// ISA troposphere lapse rate in K/m.
+const ISA_LAPSE_RATE: f64 = 0.0065;
+
+/// ISA tropopause altitude in meters, the base of the isothermal layer.
+const TROPOPAUSE_ALTITUDE_M: f64 = 11000.0;
+
+/// ISA pressure at the tropopause in hPa.
+const TROPOPAUSE_PRESSURE_HPA: f64 = 226.32;
+
+/// ISA temperature of the isothermal layer from 11 to 20 km, in Kelvin.
+const TROPOPAUSE_TEMPERATURE_K: f64 = 216.65;
+
+/// Altimeter correction near sea level, in feet per hPa of QNH difference.
+const FEET_PER_HPA: f64 = 27.0;
+
+/// Calculate pressure altitude from a static pressure.
+///
+/// Uses the ISA troposphere and the isothermal layer above it up to 20 km,
+/// relative to the 1013.25 hPa datum and independent of the local QNH.
+///
+/// # Parameters
+/// - 'static_pressure_hpa': Measured static pressure in hPa.
+///
+/// # Returns
+/// Pressure altitude in meters, or NaN if the pressure is not positive.
+pub fn pressure_altitude(static_pressure_hpa: f64) -> f64 {
+    if static_pressure_hpa.is_nan() || static_pressure_hpa <= 0.0 {
+        return f64::NAN;
+    }
+
+    if static_pressure_hpa < TROPOPAUSE_PRESSURE_HPA {
+        let scale_height = DRY_AIR_GAS_CONSTANT * TROPOPAUSE_TEMPERATURE_K / STANDARD_GRAVITY;
+        return TROPOPAUSE_ALTITUDE_M + scale_height * Float::ln(TROPOPAUSE_PRESSURE_HPA / static_pressure_hpa);
+    }
+
+    let exponent = DRY_AIR_GAS_CONSTANT * ISA_LAPSE_RATE / STANDARD_GRAVITY;
+
+    ISA_TEMPERATURE_K / ISA_LAPSE_RATE * (1.0 - Float::powf(static_pressure_hpa / STANDARD_PRESSURE_HPA, exponent))
+}
+
+/// Calculate pressure altitude in feet from a static pressure.
+///
+/// Uses the same layered ISA and 1013.25 hPa datum as [`pressure_altitude`],
+/// so pressures below the 226.32 hPa of the tropopause fall in the isothermal
+/// layer.
+///
+/// # Parameters
+/// - 'pressure_hpa': Measured static pressure in hPa.
+///
+/// # Returns
+/// Pressure altitude in feet, or NaN if the pressure is not positive.
+pub fn pressure_to_altitude_ft(pressure_hpa: f64) -> f64 {
+    pressure_altitude(pressure_hpa) * FEET_PER_METER
+}
+
+/// Calculate the flight level of a static pressure.
+///
+/// Covers the ISA troposphere and the isothermal layer above it, i.e. flight
+/// levels up to FL650.
+///
+/// # Parameters
+/// - 'pressure_hpa': Measured static pressure in hPa.
+///
+/// # Returns
+/// Pressure altitude in hundreds of feet, e.g. 350.0 for FL350.
+pub fn flight_level(pressure_hpa: f64) -> f64 {
+    pressure_altitude(pressure_hpa) * FEET_PER_METER / 100.0
+}
+
+/// Calculate the static pressure at a flight level.
+///
+/// # Parameters
+/// - 'fl': Flight level in hundreds of feet.
+///
+/// # Returns
+/// Static pressure in hPa.
+pub fn flight_level_to_pressure_hpa(fl: f64) -> f64 {
+    let altitude_m = fl * 100.0 / FEET_PER_METER;
+    if altitude_m > TROPOPAUSE_ALTITUDE_M {
+        let scale_height = DRY_AIR_GAS_CONSTANT * TROPOPAUSE_TEMPERATURE_K / STANDARD_GRAVITY;
+        return TROPOPAUSE_PRESSURE_HPA * Float::exp(-(altitude_m - TROPOPAUSE_ALTITUDE_M) / scale_height);
+    }
+
+    let exponent = STANDARD_GRAVITY / (DRY_AIR_GAS_CONSTANT * ISA_LAPSE_RATE);
+
+    STANDARD_PRESSURE_HPA * Float::powf(1.0 - ISA_LAPSE_RATE * altitude_m / ISA_TEMPERATURE_K, exponent)
+}
+
+/// Convert an altimeter reading at a QNH setting to pressure altitude.
+///
+/// Applies the standard correction of about 27 ft per hPa, which holds near sea
+/// level.
+///
+/// # Parameters
+/// - 'indicated_alt_m': Altitude indicated with the altimeter set to 'qnh_hpa', in meters.
+/// - 'qnh_hpa': Altimeter setting in hPa.
+///
+/// # Returns
+/// Pressure altitude in meters.
+pub fn indicated_to_pressure_altitude(indicated_alt_m: f64, qnh_hpa: f64) -> f64 {
+    indicated_alt_m + (STANDARD_PRESSURE_HPA - qnh_hpa) * FEET_PER_HPA / FEET_PER_METER
+}
+
+/// Calculate the altimeter setting (QNH) at a field of known elevation.
+///
+/// Reduces the station pressure to sea level through the ISA troposphere, so an
+/// altimeter set to the result reads the field elevation on the ground.
+///
+/// # Parameters
+/// - 'static_pressure_hpa': Static pressure measured at the field in hPa.
+/// - 'field_elevation_m': Elevation of the field in meters.
+///
+/// # Returns
+/// QNH in hPa.
+pub fn qnh_from_field(static_pressure_hpa: f64, field_elevation_m: f64) -> f64 {
+    let exponent = STANDARD_GRAVITY / (DRY_AIR_GAS_CONSTANT * ISA_LAPSE_RATE);
+
+    static_pressure_hpa / Float::powf(1.0 - ISA_LAPSE_RATE * field_elevation_m / ISA_TEMPERATURE_K, exponent)
+}
+
+/// Calculate density altitude from pressure and temperature.
+///
+/// Finds the altitude in the ISA troposphere where the air has the same density
+/// as air at the given pressure and temperature.
+///
+/// # Parameters
+/// - 'pressure_hpa': Static pressure in hPa.
+/// - 'temperature_c': Outside air temperature in Celsius.
+///
+/// # Returns
+/// Density altitude in meters.
+pub fn density_altitude(pressure_hpa: f64, temperature_c: f64) -> f64 {
+    let density = pressure_hpa * 100.0 / (DRY_AIR_GAS_CONSTANT * (temperature_c + KELVIN_OFFSET));
+    let sea_level_density = STANDARD_PRESSURE_HPA * 100.0 / (DRY_AIR_GAS_CONSTANT * ISA_TEMPERATURE_K);
+
+    // ISA density falls as (T / T0)^(g / (R * L) - 1)
+    let exponent = 1.0 / (STANDARD_GRAVITY / (DRY_AIR_GAS_CONSTANT * ISA_LAPSE_RATE) - 1.0);
+
+    ISA_TEMPERATURE_K / ISA_LAPSE_RATE * (1.0 - Float::powf(density / sea_level_density, exponent))
+}
+
+/// Correct an altimeter reading for a non-standard temperature.
+///
+/// Implements the ICAO cold temperature correction. Cold air is denser than the
+/// ISA assumes, so the aircraft is lower than indicated, by an amount that grows
+/// with the height above the altimeter setting source.
+///
+/// # Parameters
+/// - 'indicated_alt_m': Altitude indicated with the altimeter set to the station QNH, in meters.
+/// - 'station_alt_m': Elevation of the station providing the QNH, in meters.
+/// - 'oat_c': Outside air temperature at the station in Celsius.
+///
+/// # Returns
+/// True altitude in meters.
+pub fn temperature_corrected_altitude(indicated_alt_m: f64, station_alt_m: f64, oat_c: f64) -> f64 {
+    let isa_temperature_c = ISA_TEMPERATURE_K - KELVIN_OFFSET;
+    let sea_level_temperature_c = oat_c + ISA_LAPSE_RATE * station_alt_m; // Station temperature reduced to sea level
+    let height = indicated_alt_m - station_alt_m; // Height above the station
+
+    let correction = height * (isa_temperature_c - sea_level_temperature_c)
+        / (KELVIN_OFFSET + sea_level_temperature_c - 0.5 * ISA_LAPSE_RATE * (height + station_alt_m));
+
+    indicated_alt_m - correction
+}
+
+/// Convert a pressure altitude to true altitude from the outside air temperature.
+///
+/// The deviation of the outside air temperature from ISA at the pressure
+/// altitude is taken to hold through the whole column above the station. Each
+/// slice of that column is thicker or thinner than standard by the ratio of its
+/// actual to its ISA temperature, which integrates in closed form over the ISA
+/// troposphere.
+///
+/// # Parameters
+/// - 'pressure_alt_m': Pressure altitude in meters.
+/// - 'oat_c': Outside air temperature at the pressure altitude in Celsius.
+/// - 'station_alt_m': Elevation of the station the altitude is measured above, in meters.
+///
+/// # Returns
+/// True altitude in meters.
+pub fn pressure_to_true_altitude(pressure_alt_m: f64, oat_c: f64, station_alt_m: f64) -> f64 {
+    let isa_at = |altitude_m: f64| ISA_TEMPERATURE_K - ISA_LAPSE_RATE * altitude_m; // ISA temperature in Kelvin
+    let deviation = oat_c + KELVIN_OFFSET - isa_at(pressure_alt_m);
+
+    pressure_alt_m + deviation / ISA_LAPSE_RATE * Float::ln(isa_at(station_alt_m) / isa_at(pressure_alt_m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pressure_altitude() {
+        // Test the standard datum and a standard pressure level
+        assert!(pressure_altitude(1013.25).abs() < 1e-9);
+        assert!((pressure_altitude(500.0) - 5574.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_pressure_to_altitude_ft() {
+        // Test the datum and the tropopause
+        assert!(pressure_to_altitude_ft(1013.25).abs() < 1e-6);
+        assert!((pressure_to_altitude_ft(226.32) - 36089.0).abs() < 5.0);
+
+        // Above the tropopause the isothermal layer applies
+        assert!((pressure_to_altitude_ft(200.0) - 38662.0).abs() < 5.0);
+
+        // A pressure that is not positive has no altitude
+        assert!(pressure_to_altitude_ft(0.0).is_nan());
+        assert!(pressure_to_altitude_ft(-10.0).is_nan());
+        assert!(pressure_to_altitude_ft(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_flight_level() {
+        // Test FL180 against its standard pressure and back
+        assert!((flight_level_to_pressure_hpa(180.0) - 506.0).abs() < 1.0);
+        assert!((flight_level(flight_level_to_pressure_hpa(180.0)) - 180.0).abs() < 1e-9);
+        assert!(flight_level(STANDARD_PRESSURE_HPA).abs() < 1e-9);
+
+        // Above FL360 the isothermal layer applies, 178.75 hPa is FL410
+        assert!((flight_level(178.75) - 410.0).abs() < 0.1);
+        assert!((flight_level_to_pressure_hpa(410.0) - 178.75).abs() < 0.05);
+        assert!((flight_level(flight_level_to_pressure_hpa(410.0)) - 410.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_indicated_to_pressure_altitude() {
+        // A standard altimeter setting needs no correction
+        assert!((indicated_to_pressure_altitude(1500.0, 1013.25) - 1500.0).abs() < 1e-9);
+
+        // A 10 hPa lower QNH adds about 270 ft
+        let corrected = indicated_to_pressure_altitude(1500.0, 1003.25);
+        assert!(((corrected - 1500.0) * FEET_PER_METER - 270.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_qnh_from_field() {
+        // A field at 300 m on a standard day needs the standard setting
+        let station_pressure = flight_level_to_pressure_hpa(300.0 * FEET_PER_METER / 100.0);
+        assert!((station_pressure - 977.7).abs() < 0.1);
+        assert!((qnh_from_field(station_pressure, 300.0) - 1013.25).abs() < 1e-6);
+
+        // A lower station pressure gives a lower setting
+        assert!(qnh_from_field(970.0, 300.0) < 1006.0);
+        assert!((qnh_from_field(1000.0, 0.0) - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_density_altitude() {
+        // A standard day at sea level has zero density altitude
+        assert!(density_altitude(1013.25, 15.0).abs() < 1e-6);
+
+        // A hot day at sea level behaves like a higher airfield
+        let altitude_ft = density_altitude(1013.0, 35.0) * FEET_PER_METER;
+        assert!((altitude_ft - 2290.0).abs() < 50.0);
+    }
+
+    #[test]
+    fn test_temperature_corrected_altitude() {
+        // An ISA day at the station needs no correction
+        let isa_oat = 15.0 - ISA_LAPSE_RATE * 500.0;
+        assert!((temperature_corrected_altitude(1500.0, 500.0, isa_oat) - 1500.0).abs() < 1e-9);
+
+        // At ISA -15 °C the aircraft is about 5.6 % of its height above the station lower
+        let corrected = temperature_corrected_altitude(1500.0, 500.0, isa_oat - 15.0);
+        assert!(corrected < 1500.0);
+        assert!((1500.0 - corrected - 55.9).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_pressure_to_true_altitude() {
+        // A standard day needs no correction
+        let isa_c = ISA_TEMPERATURE_K - KELVIN_OFFSET - ISA_LAPSE_RATE * 3000.0;
+        assert!((pressure_to_true_altitude(3000.0, isa_c, 0.0) - 3000.0).abs() < 1e-9);
+        assert!((pressure_to_true_altitude(3000.0, isa_c, 500.0) - 3000.0).abs() < 1e-9);
+
+        // A day 20 degrees colder puts the aircraft about 7 % lower
+        let cold = pressure_to_true_altitude(3000.0, isa_c - 20.0, 0.0);
+        assert!((cold - 2784.4).abs() < 0.5);
+
+        // Only the column above the station is corrected
+        assert!(pressure_to_true_altitude(3000.0, isa_c - 20.0, 1000.0) > cold);
+        assert!((pressure_to_true_altitude(500.0, -30.0, 500.0) - 500.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{determine_zone, AtmosphereZone};
+
+/// Kalman filter smoothing a noisy barometric altitude.
+///
+/// Tracks altitude and vertical speed with a constant velocity model, so steady
+/// climbs are followed without lag. Needs no allocation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltitudeFilter {
+    process_noise: f64,
+    measurement_noise: f64,
+    altitude_m: f64,
+    velocity_mps: f64,
+    covariance: [[f64; 2]; 2],
+    initialized: bool,
+}
+
+impl AltitudeFilter {
+    /// Create a filter that starts from its first measurement.
+    ///
+    /// # Parameters
+    /// - 'process_noise': Variance of the vertical acceleration in (m/s²)². Higher values follow manoeuvres faster.
+    /// - 'measurement_noise': Variance of the measured altitude in m². Higher values smooth more.
+    ///
+    /// # Returns
+    /// Filter with no measurements yet.
+    pub fn new(process_noise: f64, measurement_noise: f64) -> Self {
+        AltitudeFilter {
+            process_noise,
+            measurement_noise,
+            altitude_m: 0.0,
+            velocity_mps: 0.0,
+            covariance: [[0.0; 2]; 2],
+            initialized: false,
+        }
+    }
+
+    /// Add a measurement and estimate the current altitude.
+    ///
+    /// # Parameters
+    /// - 'measured_altitude_m': Measured altitude in meters.
+    /// - 'dt_s': Time since the previous measurement in seconds.
+    ///
+    /// # Returns
+    /// Filtered altitude in meters.
+    pub fn update(&mut self, measured_altitude_m: f64, dt_s: f64) -> f64 {
+        if !self.initialized {
+            self.altitude_m = measured_altitude_m;
+            self.covariance = [[self.measurement_noise, 0.0], [0.0, self.measurement_noise]];
+            self.initialized = true;
+            return self.altitude_m;
+        }
+
+        // Predict with the current vertical speed
+        let [[p00, p01], [p10, p11]] = self.covariance;
+        let q = self.process_noise;
+        let dt2 = dt_s * dt_s;
+
+        self.altitude_m += self.velocity_mps * dt_s;
+        let p00 = p00 + dt_s * (p10 + p01) + dt2 * p11 + q * dt2 * dt2 / 4.0;
+        let p01 = p01 + dt_s * p11 + q * dt2 * dt_s / 2.0;
+        let p10 = p10 + dt_s * p11 + q * dt2 * dt_s / 2.0;
+        let p11 = p11 + q * dt2;
+
+        // Correct towards the measurement
+        let innovation = measured_altitude_m - self.altitude_m;
+        let s = p00 + self.measurement_noise;
+        let (k0, k1) = (p00 / s, p10 / s);
+
+        self.altitude_m += k0 * innovation;
+        self.velocity_mps += k1 * innovation;
+        self.covariance = [[(1.0 - k0) * p00, (1.0 - k0) * p01], [p10 - k1 * p00, p11 - k1 * p01]];
+
+        self.altitude_m
+    }
+
+    /// Current filtered altitude in meters.
+    pub fn altitude(&self) -> f64 {
+        self.altitude_m
+    }
+
+    /// Current estimated vertical speed in m/s, positive when climbing.
+    pub fn velocity(&self) -> f64 {
+        self.velocity_mps
+    }
+}
+
+/// Moving average over the last `N` pressure samples.
+///
+/// Samples are kept in a fixed array, so no allocation is needed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PressureSmoother<const N: usize> {
+    samples: [f64; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> PressureSmoother<N> {
+    /// Create a smoother with no samples.
+    pub const fn new() -> Self {
+        PressureSmoother {
+            samples: [0.0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Add a sample and average the stored samples.
+    ///
+    /// Until `N` samples have arrived, only the samples so far are averaged.
+    ///
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    ///
+    /// # Returns
+    /// Mean of the last `N` pressures in kPa.
+    pub fn push(&mut self, pressure_kpa: f64) -> f64 {
+        if N == 0 {
+            return pressure_kpa; // Nothing can be stored, so nothing is smoothed
+        }
+
+        self.samples[self.next] = pressure_kpa;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+
+        self.samples[..self.len].iter().sum::<f64>() / self.len as f64
+    }
+}
+
+impl<const N: usize> Default for PressureSmoother<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Detector for the sample where a flight moves into another zone.
+///
+/// Keeps only the zone of the previous sample, so no allocation is needed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZoneTransitionDetector {
+    zone: Option<AtmosphereZone>,
+}
+
+impl ZoneTransitionDetector {
+    /// Create a detector that has seen no samples.
+    pub const fn new() -> Self {
+        ZoneTransitionDetector { zone: None }
+    }
+
+    /// Add a sample and check whether it is in a different zone than the last one.
+    ///
+    /// The first sample only sets the starting zone. Samples that are NaN or
+    /// infinite are ignored, so a dropout doesn't report a jump out of the zone.
+    ///
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    ///
+    /// # Returns
+    /// Zones before and after the change, or None if the zone is unchanged.
+    pub fn update(&mut self, altitude_m: f64) -> Option<(AtmosphereZone, AtmosphereZone)> {
+        if !altitude_m.is_finite() {
+            return None;
+        }
+
+        let zone = determine_zone(altitude_m);
+        let previous = self.zone.replace(zone)?;
+
+        (previous != zone).then_some((previous, zone))
+    }
+
+    /// Zone of the last sample, or None before the first sample.
+    pub fn zone(&self) -> Option<AtmosphereZone> {
+        self.zone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic noise in [-1, 1) from a linear congruential generator.
+    fn noise(state: &mut u32) -> f64 {
+        *state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        f64::from(*state) / f64::from(u32::MAX) * 2.0 - 1.0
+    }
+
+    fn variance(values: &[f64], mean: f64) -> f64 {
+        values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn test_filter_reduces_noise() {
+        // Test a constant altitude measured with ±5 m of noise
+        let mut filter = AltitudeFilter::new(0.01, 25.0);
+        let mut state = 1;
+        let mut measured = [0.0; 200];
+        let mut filtered = [0.0; 200];
+
+        for i in 0..200 {
+            measured[i] = 1000.0 + 5.0 * noise(&mut state);
+            filtered[i] = filter.update(measured[i], 0.1);
+        }
+
+        // Skip the first samples while the filter settles
+        assert!(variance(&filtered[50..], 1000.0) < variance(&measured[50..], 1000.0) / 4.0);
+        assert!((filter.altitude() - 1000.0).abs() < 2.0);
+        assert!(filter.velocity().abs() < 1.0);
+    }
+
+    #[test]
+    fn test_smoother_warm_up() {
+        // Test that the first samples are averaged on their own
+        let mut smoother = PressureSmoother::<4>::new();
+
+        assert!((smoother.push(100.0) - 100.0).abs() < 1e-12);
+        assert!((smoother.push(98.0) - 99.0).abs() < 1e-12);
+        assert!((smoother.push(96.0) - 98.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_smoother_steady_state() {
+        // Test that only the last N samples are averaged once the window is full
+        let mut smoother = PressureSmoother::<3>::new();
+
+        for pressure in [50.0, 90.0, 90.0, 90.0] {
+            smoother.push(pressure);
+        }
+        assert!((smoother.push(93.0) - 91.0).abs() < 1e-12);
+
+        // An empty window passes samples through
+        assert!((PressureSmoother::<0>::new().push(90.0) - 90.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zone_transition_detector() {
+        // Test a balloon ascent sampled every 50 m through the tropopause
+        let mut detector = ZoneTransitionDetector::new();
+        let mut transitions = 0;
+
+        for step in 0..=300 {
+            let altitude_m = 5000.0 + 50.0 * f64::from(step);
+
+            if let Some(transition) = detector.update(altitude_m) {
+                assert_eq!(
+                    transition,
+                    (AtmosphereZone::Troposphere, AtmosphereZone::LowerStratosphere)
+                );
+                assert!((altitude_m - 11000.0).abs() <= 50.0);
+                transitions += 1;
+            }
+        }
+
+        assert_eq!(transitions, 1);
+        assert_eq!(detector.zone(), Some(AtmosphereZone::LowerStratosphere));
+
+        // Descending back reports the reverse transition
+        assert_eq!(
+            detector.update(10000.0),
+            Some((AtmosphereZone::LowerStratosphere, AtmosphereZone::Troposphere))
+        );
+    }
+
+    #[test]
+    fn test_zone_transition_detector_ignores_dropouts() {
+        // Test that non-finite samples neither report nor change the zone
+        let mut detector = ZoneTransitionDetector::new();
+        assert_eq!(detector.update(f64::NAN), None);
+        assert_eq!(detector.zone(), None);
+
+        detector.update(5000.0);
+        for sample in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(detector.update(sample), None);
+            assert_eq!(detector.zone(), Some(AtmosphereZone::Troposphere));
+        }
+        assert_eq!(detector.update(6000.0), None);
+    }
+}
@@ -0,0 +1,57 @@
+use core::fmt::{self, Write};
+
+/// Writer that fills a byte buffer and fails once it is full.
+struct BufferWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Write for BufferWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Format an altitude into a caller-provided buffer.
+///
+/// Needs no allocator, so it works on `no_std` targets without `format!`.
+///
+/// # Parameters
+/// - 'altitude_m': Altitude in meters.
+/// - 'decimals': Number of digits after the decimal point, rounding the rest.
+/// - 'buf': Buffer the text is written into.
+///
+/// # Returns
+/// The formatted altitude borrowed from 'buf', or an error if it does not fit.
+pub fn format_altitude(altitude_m: f64, decimals: u8, buf: &mut [u8]) -> Result<&str, fmt::Error> {
+    let mut writer = BufferWriter { buf, len: 0 };
+    write!(writer, "{:.*}", usize::from(decimals), altitude_m)?;
+
+    let BufferWriter { buf, len } = writer;
+    core::str::from_utf8(&buf[..len]).map_err(|_| fmt::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_altitude() {
+        // Test rounding to each precision
+        let mut buf = [0u8; 16];
+        assert_eq!(format_altitude(1234.567, 0, &mut buf), Ok("1235"));
+        assert_eq!(format_altitude(1234.567, 1, &mut buf), Ok("1234.6"));
+        assert_eq!(format_altitude(1234.567, 2, &mut buf), Ok("1234.57"));
+        assert_eq!(format_altitude(-12.0, 1, &mut buf), Ok("-12.0"));
+
+        // A buffer that is too short is an error
+        assert_eq!(format_altitude(1234.567, 2, &mut buf[..4]), Err(fmt::Error));
+    }
+}
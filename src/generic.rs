@@ -0,0 +1,1320 @@
+//! The atmosphere model over any [`Float`] type.
+//!
+//! The functions at the crate root are the `f64` forms of these. Call them
+//! from here with `f32` on targets where `f64` is emulated in software.
+
+use num_traits::Float;
+
+use crate::{
+    AltitudeCalculator, AltitudeCalculatorBuilder, AltitudeError, AltitudeUnit, AtmosphereModel, AtmosphereProperties,
+    AtmosphereZone, BoundsPolicy, ConfigError, Planet, SeaLevelReference, BOLTZMANN_CONSTANT, DRY_AIR_GAS_CONSTANT,
+    FEET_PER_METER, HEAT_CAPACITY_RATIO, KELVIN_OFFSET, STANDARD_GRAVITY,
+};
+
+/// Lowest altitude the model is evaluated at, in meters.
+const LOWEST_ALTITUDE_M: f64 = -500.0;
+
+/// Highest altitude the model is evaluated at, in meters.
+const HIGHEST_ALTITUDE_M: f64 = 84_852.0;
+
+/// Lowest altitude of the mesosphere, in meters.
+const MESOSPHERE_BASE_M: f64 = 47_000.0;
+
+/// Layers of the US Standard Atmosphere 1976 as base altitude in m, base
+/// temperature in K, lapse rate in K/m and base pressure in kPa. A negative
+/// lapse rate means the layer warms with height.
+const US_1976_LAYERS: [(f64, f64, f64, f64); 7] = [
+    (0.0, 288.15, 0.0065, 101.325),
+    (11_000.0, 216.65, 0.0, 22.632_1),
+    (20_000.0, 216.65, -0.001, 5.474_89),
+    (32_000.0, 228.65, -0.0028, 0.868_019),
+    (MESOSPHERE_BASE_M, 270.65, 0.0, 0.110_906),
+    (51_000.0, 270.65, 0.0028, 0.066_938_7),
+    (71_000.0, 214.65, 0.002, 0.003_956_42),
+];
+
+/// Layers of the US Standard Atmosphere 1976 in the units of the standard, see [`crate::LAYERS`].
+pub(crate) const fn standard_layers() -> [(f64, f64, f64, f64); 7] {
+    let mut layers = [(0.0, 0.0, 0.0, 0.0); 7];
+    let mut i = 0;
+    while i < layers.len() {
+        let (base_m, base_k, lapse, base_kpa) = US_1976_LAYERS[i];
+        layers[i] = (base_m, base_k, base_kpa * 1000.0, -lapse);
+        i += 1;
+    }
+
+    layers
+}
+
+/// Layers of the US Standard Atmosphere 1976 the mesosphere zone is made of.
+const MESOSPHERE_LAYERS: &[(f64, f64, f64, f64)] = &[US_1976_LAYERS[4], US_1976_LAYERS[5], US_1976_LAYERS[6]];
+
+/// Hydrostatic constant g0 * M / R* of the US Standard Atmosphere 1976, in K/m.
+const HYDROSTATIC_CONSTANT: f64 = 0.034_163_195;
+
+/// Effective collision diameter of an air molecule, in meters.
+const AIR_COLLISION_DIAMETER_M: f64 = 3.62e-10;
+
+/// Specific heat of dry air at constant pressure in J/(kg·K).
+const AIR_SPECIFIC_HEAT: f64 = 1005.0;
+
+/// Poisson constant R/cp of dry air, dimensionless.
+const POISSON_CONSTANT: f64 = 0.2854;
+
+/// Volume fraction of oxygen in dry air.
+const OXYGEN_FRACTION: f64 = 0.2095;
+
+/// Altitude above which the oxygen partial pressure risks hypoxia, in meters.
+const HYPOXIA_ALTITUDE_M: f64 = 3_000.0;
+
+/// Time of useful consciousness without supplemental oxygen as cabin altitude in
+/// meters and seconds. The midpoints of the FAA bands from FL180 to FL500.
+const USEFUL_CONSCIOUSNESS: [(f64, f64); 9] = [
+    (5_486.0, 1_500.0),
+    (6_706.0, 600.0),
+    (7_620.0, 270.0),
+    (8_534.0, 165.0),
+    (9_144.0, 90.0),
+    (10_668.0, 45.0),
+    (12_192.0, 17.5),
+    (13_106.0, 10.5),
+    (15_240.0, 7.5),
+];
+
+/// Scale height of the single exponential approximation, in meters.
+const EXPONENTIAL_SCALE_HEIGHT_M: f64 = 8_500.0;
+
+/// Datum pressure of the NASA Mars model, in kPa.
+const MARS_SURFACE_PRESSURE_KPA: f64 = 0.699;
+
+/// Exponential pressure decay of the NASA Mars model, per meter.
+const MARS_PRESSURE_DECAY: f64 = 0.000_09;
+
+/// Steepest troposphere lapse rate a calculator can be built with, just above the dry adiabatic rate, in K/m.
+const MAX_LAPSE_RATE: f64 = 0.01;
+
+/// Top of the lower zone of the NASA Mars model, in meters.
+const MARS_LOWER_TOP_M: f64 = 7_000.0;
+
+/// Lowest altitude the Mars model is evaluated at, the floor of Hellas Planitia, in meters.
+const MARS_LOWEST_ALTITUDE_M: f64 = -8_200.0;
+
+/// Highest altitude the Mars model is evaluated at, in meters.
+const MARS_HIGHEST_ALTITUDE_M: f64 = 100_000.0;
+
+/// Specific gas constant of the Martian atmosphere in kJ/(kg·K).
+const MARS_GAS_CONSTANT: f64 = 0.1921;
+
+/// Convert a model constant to the float type in use.
+fn cast<F: Float>(value: f64) -> F {
+    F::from(value).unwrap_or_else(F::nan)
+}
+
+/// Convert a value back to `f64` for error payloads.
+fn as_f64<F: Float>(value: F) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// See [`crate::determine_zone`].
+///
+/// Comparing in `f64` is exact for `f32` altitudes and keeps the zone
+/// boundaries in the `const fn` form only.
+pub fn determine_zone<F: Float>(altitude_m: F) -> AtmosphereZone {
+    crate::determine_zone(as_f64(altitude_m))
+}
+
+impl<F: Float> Default for SeaLevelReference<F> {
+    /// Sea-level conditions of the NASA model, anchored to the standard
+    /// sea-level pressure.
+    fn default() -> Self {
+        SeaLevelReference {
+            pressure_kpa: cast(AltitudeCalculator::SEA_LEVEL_PRESSURE_KPA),
+            temperature_c: cast(AltitudeCalculator::SEA_LEVEL_TEMPERATURE_C),
+        }
+    }
+}
+
+impl<F: Float> Default for AltitudeCalculator<F> {
+    /// The NASA model with its standard sea level and lapse rate.
+    fn default() -> Self {
+        AltitudeCalculator::new(SeaLevelReference::default(), cast(AltitudeCalculator::LAPSE_RATE))
+    }
+}
+
+impl<F: Float> Default for AltitudeCalculatorBuilder<F> {
+    /// Settings that build [`AltitudeCalculator::default`].
+    fn default() -> Self {
+        let reference = SeaLevelReference::default();
+
+        AltitudeCalculatorBuilder {
+            sea_level_pressure_kpa: reference.pressure_kpa,
+            sea_level_temperature_c: reference.temperature_c,
+            lapse_rate_k_per_m: cast(AltitudeCalculator::LAPSE_RATE),
+            output_unit: AltitudeUnit::Meters,
+            model: AtmosphereModel::Nasa1960,
+            planet: Planet::Earth,
+            bounds: BoundsPolicy::Error,
+        }
+    }
+}
+
+impl<F: Float> AltitudeCalculatorBuilder<F> {
+    /// Set the sea-level pressure in kPa.
+    pub fn sea_level_pressure(mut self, pressure_kpa: F) -> Self {
+        self.sea_level_pressure_kpa = pressure_kpa;
+        self
+    }
+
+    /// Set the sea-level temperature in Celsius.
+    pub fn sea_level_temperature(mut self, temperature_c: F) -> Self {
+        self.sea_level_temperature_c = temperature_c;
+        self
+    }
+
+    /// Set the troposphere lapse rate in K/m.
+    pub fn lapse_rate(mut self, lapse_rate_k_per_m: F) -> Self {
+        self.lapse_rate_k_per_m = lapse_rate_k_per_m;
+        self
+    }
+
+    /// Set the unit calculated altitudes are returned in.
+    pub fn output_unit(mut self, unit: AltitudeUnit) -> Self {
+        self.output_unit = unit;
+        self
+    }
+
+    /// Set the standard atmosphere to calculate with.
+    ///
+    /// The US Standard Atmosphere 1976 has fixed layers, so it ignores the sea-level
+    /// and lapse rate settings.
+    pub fn model(mut self, model: AtmosphereModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the planet whose atmosphere is calculated.
+    ///
+    /// Mars follows NASA's simplified Mars model, which ignores every setting but the
+    /// output unit. Its altitudes are measured from the Mars datum.
+    pub fn planet(mut self, planet: Planet) -> Self {
+        self.planet = planet;
+        self
+    }
+
+    /// Set how pressures outside the requested zone are handled.
+    ///
+    /// With [`BoundsPolicy::Clamp`] a reading beyond a zone limit returns the
+    /// altitude of that boundary, which keeps a live display from dropping frames.
+    /// Non-finite readings are still errors.
+    pub fn bounds_policy(mut self, bounds: BoundsPolicy) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Create the configured calculator.
+    ///
+    /// Every setting must be finite, the sea-level pressure positive, the
+    /// sea-level temperature above absolute zero and the lapse rate positive
+    /// and no steeper than 0.01 K/m.
+    pub fn build(self) -> Result<AltitudeCalculator<F>, ConfigError> {
+        let settings = [
+            self.sea_level_pressure_kpa,
+            self.sea_level_temperature_c,
+            self.lapse_rate_k_per_m,
+        ];
+        if settings.iter().any(|setting| !setting.is_finite()) {
+            return Err(ConfigError::InvalidSetting);
+        }
+        if self.sea_level_pressure_kpa <= F::zero() {
+            return Err(ConfigError::NonPositivePressure);
+        }
+        if self.sea_level_temperature_c < cast(-KELVIN_OFFSET) {
+            return Err(ConfigError::BelowAbsoluteZero);
+        }
+        if self.lapse_rate_k_per_m <= F::zero() || self.lapse_rate_k_per_m > cast(MAX_LAPSE_RATE) {
+            return Err(ConfigError::InvalidLapseRate);
+        }
+
+        let reference = SeaLevelReference {
+            pressure_kpa: self.sea_level_pressure_kpa,
+            temperature_c: self.sea_level_temperature_c,
+        };
+
+        Ok(AltitudeCalculator {
+            output_unit: self.output_unit,
+            model: self.model,
+            planet: self.planet,
+            bounds: self.bounds,
+            ..AltitudeCalculator::new(reference, self.lapse_rate_k_per_m)
+        })
+    }
+}
+
+impl<F: Float> AltitudeCalculator<F> {
+    /// Create a calculator with non-standard troposphere parameters.
+    ///
+    /// # Parameters
+    /// - 'reference': Sea-level pressure and temperature.
+    /// - 'lapse_rate_k_per_m': Rate the troposphere cools with height in K/m. The NASA model uses 0.00649.
+    ///
+    /// # Returns
+    /// Calculator using the given parameters.
+    pub fn new(reference: SeaLevelReference<F>, lapse_rate_k_per_m: F) -> Self {
+        AltitudeCalculator {
+            reference,
+            lapse_rate_k_per_m,
+            output_unit: AltitudeUnit::Meters,
+            model: AtmosphereModel::Nasa1960,
+            planet: Planet::Earth,
+            bounds: BoundsPolicy::Error,
+        }
+    }
+
+    /// Start configuring a calculator from the defaults.
+    pub fn builder() -> AltitudeCalculatorBuilder<F> {
+        AltitudeCalculatorBuilder::default()
+    }
+
+    /// Create a calculator for the International Standard Atmosphere.
+    ///
+    /// ISA sea level is 15 °C and 101.325 kPa with a 0.0065 K/m lapse rate, where
+    /// the NASA constants of [`AltitudeCalculator::default`] are 15.04 °C and
+    /// 0.00649 K/m. Below 32 km the ISA matches the US Standard Atmosphere 1976,
+    /// so this uses [`AtmosphereModel::UsStandard1976`] and reproduces the ISA
+    /// tables exactly. The NASA curve fit differs from them by a few meters.
+    pub fn isa() -> Self {
+        let reference = SeaLevelReference {
+            pressure_kpa: cast(US_1976_LAYERS[0].3),
+            temperature_c: cast(US_1976_LAYERS[0].1 - KELVIN_OFFSET),
+        };
+
+        AltitudeCalculator {
+            model: AtmosphereModel::UsStandard1976,
+            ..AltitudeCalculator::new(reference, cast(US_1976_LAYERS[0].2))
+        }
+    }
+
+    /// Exponent of the troposphere pressure formula, 5.256 at the NASA lapse rate.
+    fn troposphere_exponent(&self) -> F {
+        cast::<F>(AltitudeCalculator::TROPOSPHERE_EXPONENT * AltitudeCalculator::LAPSE_RATE) / self.lapse_rate_k_per_m
+    }
+
+    /// Calculate altitude for a zone from temperature and pressure.
+    ///
+    /// The temperature is only checked to be a finite reading. Every zone
+    /// finds the altitude from pressure along the model temperature profile,
+    /// see [`crate::altitude_from_pt`] to correct for a measured temperature.
+    ///
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    ///
+    /// # Returns
+    /// Geopotential altitude in the output unit, or the reason it could not be calculated.
+    pub fn calculate_altitude(
+        &self,
+        zone: AtmosphereZone,
+        temperature_c: F,
+        pressure_kpa: F,
+    ) -> Result<F, AltitudeError> {
+        let (bottom_m, top_m) = match self.planet {
+            Planet::Earth => zone_bounds(zone),
+            Planet::Mars => mars_zone_bounds(zone),
+        };
+        let clamp = self.bounds == BoundsPolicy::Clamp;
+
+        let altitude = match self.calculate_altitude_m(zone, temperature_c, pressure_kpa) {
+            Err(AltitudeError::PressureTooHigh { .. }) if clamp => cast(bottom_m),
+            Err(AltitudeError::PressureTooLow { .. }) if clamp => cast(top_m),
+            result => result?,
+        };
+        Ok(altitude * cast(self.output_unit.per_meter()))
+    }
+
+    /// Altitude in meters, before conversion to the output unit.
+    fn calculate_altitude_m(
+        &self,
+        zone: AtmosphereZone,
+        temperature_c: F,
+        pressure_kpa: F,
+    ) -> Result<F, AltitudeError> {
+        // A faulty sensor reading would poison the altitude
+        celsius_to_kelvin_checked(temperature_c)?;
+        if !pressure_kpa.is_finite() {
+            return Err(AltitudeError::InvalidInput);
+        }
+
+        // A reference given to `new` has not been through the builder's checks
+        celsius_to_kelvin_checked(self.reference.temperature_c)?;
+        if !self.reference.pressure_kpa.is_finite() || self.reference.pressure_kpa <= F::zero() {
+            return Err(AltitudeError::InvalidInput);
+        }
+
+        if self.planet == Planet::Mars {
+            if pressure_kpa <= F::zero() {
+                return Err(AltitudeError::InvalidInput);
+            }
+
+            let (bottom_m, top_m) = mars_zone_bounds(zone);
+            let p = mars_pressure::<F>(cast(bottom_m)); // Pressure at the bottom of the zone in kPa
+            if pressure_kpa > p {
+                return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+            }
+            let p = mars_pressure::<F>(cast(top_m)); // Pressure at the top of the zone in kPa
+            if pressure_kpa <= p {
+                return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p) });
+            }
+
+            // Both Mars zones share one pressure formula
+            return Ok((cast::<F>(MARS_SURFACE_PRESSURE_KPA) / pressure_kpa).ln() / cast(MARS_PRESSURE_DECAY));
+        }
+        if self.model == AtmosphereModel::UsStandard1976 {
+            return us_1976_zone_altitude(zone, pressure_kpa);
+        }
+
+        match zone {
+            AtmosphereZone::Troposphere => {
+                let t0 = self.reference.temperature_c + cast(KELVIN_OFFSET); // Sea level temperature in Kelvin
+                let t = t0 - self.lapse_rate_k_per_m * cast(LOWEST_ALTITUDE_M); // Temperature 500 m below sea level in Kelvin
+                let p = self.reference.pressure_kpa * (t / t0).powf(self.troposphere_exponent()); // Pressure 500 m below sea level in kPa
+
+                if pressure_kpa > p {
+                    // Pressure is out of range for Troposphere
+                    return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+                }
+                // Pressure at 11 000 m on this troposphere in kPa, which moves with the reference and lapse rate
+                let (_, top_m) = zone_bounds(AtmosphereZone::Troposphere);
+                let t = t0 - self.lapse_rate_k_per_m * cast(top_m);
+                let p = self.reference.pressure_kpa * (t / t0).powf(self.troposphere_exponent());
+                if pressure_kpa <= p {
+                    return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p) });
+                }
+
+                // Invert the pressure formula for temperature, then the lapse rate for altitude
+                let t = t0 * (pressure_kpa / self.reference.pressure_kpa).powf(self.troposphere_exponent().recip());
+                let altitude = (t0 - t) / self.lapse_rate_k_per_m;
+                Ok(altitude)
+            }
+            AtmosphereZone::LowerStratosphere => {
+                let p = cast::<F>(AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA);
+
+                if pressure_kpa > p {
+                    // Pressure is out of range for lower Stratosphere
+                    return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+                }
+                let p25 = cast::<F>(AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA);
+                if pressure_kpa <= p25 {
+                    return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p25) });
+                }
+
+                let altitude =
+                    cast::<F>(11_000.0) + (pressure_kpa / p).ln() / cast(-AltitudeCalculator::LOWER_STRATOSPHERE_DECAY);
+                Ok(altitude)
+            }
+            AtmosphereZone::UpperStratosphere => {
+                let p = cast::<F>(AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA);
+
+                if pressure_kpa > p {
+                    // Pressure is out of range for Upper Stratosphere
+                    return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+                }
+
+                let zone = AtmosphereZone::UpperStratosphere;
+                let top: F = cast(MESOSPHERE_BASE_M);
+                let p = self.zone_pressure(zone, top, self.zone_temperature(zone, top)); // Pressure at 47 000 m in kPa
+                if pressure_kpa <= p {
+                    return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p) });
+                }
+
+                // Invert the pressure formula for temperature, then the linear temperature profile for altitude
+                // Temperature at 25 000 m in Kelvin
+                let t25 = cast::<F>(AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C + KELVIN_OFFSET);
+                let t = t25
+                    * (pressure_kpa / cast(AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA))
+                        .powf(cast(-1.0 / AltitudeCalculator::UPPER_STRATOSPHERE_EXPONENT))
+                    - cast(KELVIN_OFFSET);
+                let altitude = (t - cast(AltitudeCalculator::UPPER_STRATOSPHERE_BASE_TEMPERATURE_C))
+                    / cast(AltitudeCalculator::UPPER_STRATOSPHERE_WARMING_RATE);
+                Ok(altitude)
+            }
+            AtmosphereZone::Mesosphere => {
+                let scale = mesosphere_scale::<F>();
+                let p = cast::<F>(MESOSPHERE_LAYERS[0].3) * scale; // Pressure at 47 000 m in kPa
+
+                if pressure_kpa > p {
+                    // Pressure is out of range for Mesosphere
+                    return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+                }
+                let (_, top_m) = zone_bounds(AtmosphereZone::Mesosphere);
+                // Pressure at the top of the model in kPa
+                let p = layer_pressure(MESOSPHERE_LAYERS, cast::<F>(top_m)) * scale;
+                if pressure_kpa <= p {
+                    return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p) });
+                }
+
+                Ok(layer_altitude(MESOSPHERE_LAYERS, pressure_kpa / scale))
+            }
+        }
+    }
+
+    /// Calculate temperature at an altitude.
+    ///
+    /// The temperature steps down by about 11.8 K at 47 000 m, where the upper
+    /// stratosphere meets the US Standard Atmosphere 1976 mesosphere.
+    ///
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    ///
+    /// # Returns
+    /// Temperature in Celsius.
+    pub fn temperature_at_altitude(&self, altitude_m: F) -> F {
+        self.zone_temperature(determine_zone(altitude_m), altitude_m)
+    }
+
+    /// Calculate pressure at an altitude.
+    ///
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    ///
+    /// # Returns
+    /// Pressure in kPa.
+    pub fn pressure_at_altitude(&self, altitude_m: F) -> F {
+        let zone = determine_zone(altitude_m);
+
+        self.zone_pressure(zone, altitude_m, self.zone_temperature(zone, altitude_m))
+    }
+
+    /// Calculate all properties at an altitude at once.
+    ///
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    ///
+    /// # Returns
+    /// Zone, temperature, pressure and density at the altitude.
+    pub fn properties_at_altitude(&self, altitude_m: F) -> AtmosphereProperties<F> {
+        let zone = determine_zone(altitude_m);
+        let temperature_c = self.zone_temperature(zone, altitude_m);
+        let pressure_kpa = self.zone_pressure(zone, altitude_m, temperature_c);
+
+        AtmosphereProperties {
+            zone,
+            temperature_c,
+            pressure_kpa,
+            density_kgm3: self.density(pressure_kpa, temperature_c),
+        }
+    }
+
+    /// Density in kg/m³ of the planet's atmosphere at a pressure in kPa and temperature in Celsius.
+    fn density(&self, pressure_kpa: F, temperature_c: F) -> F {
+        match self.planet {
+            Planet::Earth => ideal_gas_density(pressure_kpa, temperature_c),
+            Planet::Mars => pressure_kpa / (cast::<F>(MARS_GAS_CONSTANT) * (temperature_c + cast(KELVIN_OFFSET))),
+        }
+    }
+
+    /// Temperature in Celsius at an altitude known to be in 'zone'.
+    fn zone_temperature(&self, zone: AtmosphereZone, altitude_m: F) -> F {
+        if self.planet == Planet::Mars {
+            return if altitude_m < cast(MARS_LOWER_TOP_M) {
+                cast::<F>(-31.0) - cast::<F>(0.000998) * altitude_m
+            } else {
+                cast::<F>(-23.4) - cast::<F>(0.00222) * altitude_m
+            };
+        }
+        if self.model == AtmosphereModel::UsStandard1976 {
+            return layer_temperature(&US_1976_LAYERS, altitude_m);
+        }
+
+        match zone {
+            AtmosphereZone::Troposphere => self.reference.temperature_c - self.lapse_rate_k_per_m * altitude_m,
+            AtmosphereZone::LowerStratosphere => cast(AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C), // Isothermal layer
+            AtmosphereZone::UpperStratosphere => {
+                cast::<F>(AltitudeCalculator::UPPER_STRATOSPHERE_BASE_TEMPERATURE_C)
+                    + cast::<F>(AltitudeCalculator::UPPER_STRATOSPHERE_WARMING_RATE) * altitude_m
+            }
+            AtmosphereZone::Mesosphere => layer_temperature(MESOSPHERE_LAYERS, altitude_m),
+        }
+    }
+
+    /// Pressure in kPa at an altitude known to be in 'zone', where the temperature is 't' in Celsius.
+    fn zone_pressure(&self, zone: AtmosphereZone, altitude_m: F, t: F) -> F {
+        if self.planet == Planet::Mars {
+            return mars_pressure(altitude_m);
+        }
+        if self.model == AtmosphereModel::UsStandard1976 {
+            return layer_pressure(&US_1976_LAYERS, altitude_m);
+        }
+
+        match zone {
+            AtmosphereZone::Troposphere => {
+                let t0 = self.reference.temperature_c + cast(KELVIN_OFFSET); // Sea level temperature in Kelvin
+                self.reference.pressure_kpa * ((t + cast(KELVIN_OFFSET)) / t0).powf(self.troposphere_exponent())
+            }
+            AtmosphereZone::LowerStratosphere => {
+                cast::<F>(AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA)
+                    * (cast::<F>(-AltitudeCalculator::LOWER_STRATOSPHERE_DECAY) * (altitude_m - cast(11_000.0))).exp()
+            }
+            AtmosphereZone::UpperStratosphere => {
+                // Temperature at 25 000 m in Kelvin
+                let t25 = cast::<F>(AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C + KELVIN_OFFSET);
+                cast::<F>(AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA)
+                    * ((t + cast(KELVIN_OFFSET)) / t25).powf(cast(-AltitudeCalculator::UPPER_STRATOSPHERE_EXPONENT))
+            }
+            AtmosphereZone::Mesosphere => layer_pressure(MESOSPHERE_LAYERS, altitude_m) * mesosphere_scale(),
+        }
+    }
+}
+
+/// Factor joining the US Standard Atmosphere 1976 mesosphere onto the NASA upper stratosphere at 47 000 m.
+fn mesosphere_scale<F: Float>() -> F {
+    let standard = AltitudeCalculator::default();
+    let zone = AtmosphereZone::UpperStratosphere;
+    let top: F = cast(MESOSPHERE_BASE_M);
+
+    standard.zone_pressure(zone, top, standard.zone_temperature(zone, top)) / cast(MESOSPHERE_LAYERS[0].3)
+}
+
+/// Convert a temperature to Kelvin, rejecting readings that are not finite or below absolute zero.
+pub(crate) fn celsius_to_kelvin_checked<F: Float>(temperature_c: F) -> Result<F, AltitudeError> {
+    if !temperature_c.is_finite() {
+        return Err(AltitudeError::InvalidInput);
+    }
+
+    let kelvin = temperature_c + cast(KELVIN_OFFSET);
+    if kelvin < F::zero() {
+        return Err(AltitudeError::BelowAbsoluteZero);
+    }
+
+    Ok(kelvin)
+}
+
+/// Layer containing an altitude, extending the outermost layers beyond their bounds.
+fn layer_at<F: Float>(layers: &[(f64, f64, f64, f64)], altitude_m: F) -> (f64, f64, f64, f64) {
+    let altitude_m = as_f64(altitude_m);
+
+    layers
+        .iter()
+        .rev()
+        .find(|layer| altitude_m >= layer.0)
+        .copied()
+        .unwrap_or(layers[0])
+}
+
+/// See [`crate::layer_for`].
+pub fn layer_for<F: Float>(altitude_m: F) -> usize {
+    let altitude_m = as_f64(altitude_m);
+
+    US_1976_LAYERS
+        .iter()
+        .rposition(|layer| altitude_m >= layer.0)
+        .unwrap_or(0)
+}
+
+/// Temperature in Celsius at an altitude of a layered atmosphere.
+fn layer_temperature<F: Float>(layers: &[(f64, f64, f64, f64)], altitude_m: F) -> F {
+    let (base_m, base_k, lapse, _) = layer_at(layers, altitude_m);
+
+    cast::<F>(base_k - KELVIN_OFFSET) - cast::<F>(lapse) * (altitude_m - cast(base_m))
+}
+
+/// Pressure in kPa at an altitude of a layered atmosphere.
+fn layer_pressure<F: Float>(layers: &[(f64, f64, f64, f64)], altitude_m: F) -> F {
+    let (base_m, base_k, lapse, base_kpa) = layer_at(layers, altitude_m);
+
+    if lapse == 0.0 {
+        let exponent = cast::<F>(-HYDROSTATIC_CONSTANT / base_k) * (altitude_m - cast(base_m));
+        cast::<F>(base_kpa) * exponent.exp()
+    } else {
+        let t = cast::<F>(base_k) - cast::<F>(lapse) * (altitude_m - cast(base_m)); // Temperature in Kelvin
+        cast::<F>(base_kpa) * (t / cast(base_k)).powf(cast(HYDROSTATIC_CONSTANT / lapse))
+    }
+}
+
+/// Altitude in meters of a pressure in a layered atmosphere.
+fn layer_altitude<F: Float>(layers: &[(f64, f64, f64, f64)], pressure_kpa: F) -> F {
+    // The highest layer whose base pressure is above the reading contains it
+    let (base_m, base_k, lapse, base_kpa) = layers
+        .iter()
+        .rev()
+        .find(|layer| pressure_kpa <= cast(layer.3))
+        .copied()
+        .unwrap_or(layers[0]);
+
+    let ratio = pressure_kpa / cast(base_kpa);
+    if lapse == 0.0 {
+        cast::<F>(base_m) - cast::<F>(base_k / HYDROSTATIC_CONSTANT) * ratio.ln()
+    } else {
+        let t = cast::<F>(base_k) * ratio.powf(cast(lapse / HYDROSTATIC_CONSTANT));
+        cast::<F>(base_m) + (cast::<F>(base_k) - t) / cast(lapse)
+    }
+}
+
+/// Pressure in kPa at an altitude of the NASA Mars model.
+fn mars_pressure<F: Float>(altitude_m: F) -> F {
+    cast::<F>(MARS_SURFACE_PRESSURE_KPA) * (cast::<F>(-MARS_PRESSURE_DECAY) * altitude_m).exp()
+}
+
+/// Bottom and top of a Mars zone in meters. The troposphere is the lower zone and every higher zone the upper one.
+fn mars_zone_bounds(zone: AtmosphereZone) -> (f64, f64) {
+    match zone {
+        AtmosphereZone::Troposphere => (MARS_LOWEST_ALTITUDE_M, MARS_LOWER_TOP_M),
+        _ => (MARS_LOWER_TOP_M, MARS_HIGHEST_ALTITUDE_M),
+    }
+}
+
+/// Bottom and top of a zone in meters, limited to the altitudes the model covers.
+fn zone_bounds(zone: AtmosphereZone) -> (f64, f64) {
+    match zone {
+        AtmosphereZone::Troposphere => (LOWEST_ALTITUDE_M, 11_000.0),
+        AtmosphereZone::LowerStratosphere => (11_000.0, 25_000.0),
+        AtmosphereZone::UpperStratosphere => (25_000.0, MESOSPHERE_BASE_M),
+        AtmosphereZone::Mesosphere => (MESOSPHERE_BASE_M, HIGHEST_ALTITUDE_M),
+    }
+}
+
+/// Altitude of a pressure in a zone of the US Standard Atmosphere 1976.
+fn us_1976_zone_altitude<F: Float>(zone: AtmosphereZone, pressure_kpa: F) -> Result<F, AltitudeError> {
+    let (bottom_m, top_m) = zone_bounds(zone);
+
+    let p = layer_pressure(&US_1976_LAYERS, bottom_m); // Pressure at the bottom of the zone in kPa
+    if pressure_kpa > cast(p) {
+        return Err(AltitudeError::PressureTooHigh { limit_kpa: p });
+    }
+    let p = layer_pressure(&US_1976_LAYERS, top_m); // Pressure at the top of the zone in kPa
+    if pressure_kpa <= cast(p) {
+        return Err(AltitudeError::PressureTooLow { limit_kpa: p });
+    }
+
+    Ok(layer_altitude(&US_1976_LAYERS, pressure_kpa))
+}
+
+/// See [`crate::calculate_altitude`].
+pub fn calculate_altitude<F: Float>(
+    zone: AtmosphereZone,
+    temperature_c: F,
+    pressure_kpa: F,
+) -> Result<F, AltitudeError> {
+    AltitudeCalculator::default().calculate_altitude(zone, temperature_c, pressure_kpa)
+}
+
+/// See [`crate::calculate_altitude_with_reference`].
+pub fn calculate_altitude_with_reference<F: Float>(
+    zone: AtmosphereZone,
+    temperature_c: F,
+    pressure_kpa: F,
+    reference: SeaLevelReference<F>,
+) -> Result<F, AltitudeError> {
+    let calculator = AltitudeCalculator::new(reference, cast(AltitudeCalculator::LAPSE_RATE));
+    calculator.calculate_altitude(zone, temperature_c, pressure_kpa)
+}
+
+/// See [`crate::calculate_altitude_with_model`].
+pub fn calculate_altitude_with_model<F: Float>(
+    zone: AtmosphereZone,
+    temperature_c: F,
+    pressure_kpa: F,
+    model: AtmosphereModel,
+) -> Result<F, AltitudeError> {
+    AltitudeCalculator {
+        model,
+        ..AltitudeCalculator::default()
+    }
+    .calculate_altitude(zone, temperature_c, pressure_kpa)
+}
+
+/// See [`crate::altitude_from_pressure`].
+pub fn altitude_from_pressure<F: Float>(pressure_kpa: F) -> Result<F, AltitudeError> {
+    if !pressure_kpa.is_finite() {
+        return Err(AltitudeError::InvalidInput);
+    }
+
+    let mut low: F = cast(LOWEST_ALTITUDE_M);
+    let mut high: F = cast(HIGHEST_ALTITUDE_M);
+
+    let p = pressure_at_altitude(low); // Highest pressure the model covers
+    if pressure_kpa > p {
+        return Err(AltitudeError::PressureTooHigh { limit_kpa: as_f64(p) });
+    }
+    let p = pressure_at_altitude(high); // Lowest pressure the model covers
+    if pressure_kpa < p {
+        return Err(AltitudeError::PressureTooLow { limit_kpa: as_f64(p) });
+    }
+
+    // Pressure falls with altitude, so bisect until the bracket can't shrink any further.
+    for _ in 0..128 {
+        let mid = (low + high) / cast(2.0);
+        if mid <= low || mid >= high {
+            break;
+        }
+
+        if pressure_at_altitude(mid) > pressure_kpa {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok((low + high) / cast(2.0))
+}
+
+/// See [`crate::altitude_of_pressure_level`].
+pub fn altitude_of_pressure_level<F: Float>(level_hpa: F) -> F {
+    altitude_from_pressure(level_hpa / cast(10.0)).unwrap_or_else(|_| F::nan())
+}
+
+/// See [`crate::altitude_and_zone_from_pressure`].
+pub fn altitude_and_zone_from_pressure<F: Float>(pressure_kpa: F) -> Result<(F, AtmosphereZone), AltitudeError> {
+    altitude_from_pressure(pressure_kpa).map(|altitude| (altitude, determine_zone(altitude)))
+}
+
+/// See [`crate::altitude_uncertainty`].
+pub fn altitude_uncertainty<F: Float>(pressure_kpa: F, pressure_sigma_kpa: F) -> F {
+    (altitude_pressure_sensitivity(pressure_kpa) * pressure_sigma_kpa).abs()
+}
+
+/// See [`crate::altitude_pressure_sensitivity`].
+pub fn altitude_pressure_sensitivity<F: Float>(pressure_kpa: F) -> F {
+    let Ok((altitude, zone)) = altitude_and_zone_from_pressure(pressure_kpa) else {
+        return F::nan();
+    };
+    let t = temperature_at_altitude(altitude) + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    // Every zone formula is hydrostatic, dh/dp = -T / (p * g/R), each with its own fitted value of g/R
+    let hydrostatic: F = match zone {
+        AtmosphereZone::Troposphere => cast(AltitudeCalculator::TROPOSPHERE_EXPONENT * AltitudeCalculator::LAPSE_RATE),
+        AtmosphereZone::LowerStratosphere => t * cast(AltitudeCalculator::LOWER_STRATOSPHERE_DECAY),
+        AtmosphereZone::UpperStratosphere => {
+            cast(AltitudeCalculator::UPPER_STRATOSPHERE_EXPONENT * AltitudeCalculator::UPPER_STRATOSPHERE_WARMING_RATE)
+        }
+        AtmosphereZone::Mesosphere => cast(HYDROSTATIC_CONSTANT),
+    };
+
+    -t / (pressure_kpa * hydrostatic)
+}
+
+/// See [`crate::zone_from_pressure`].
+pub fn zone_from_pressure<F: Float>(pressure_kpa: F) -> AtmosphereZone {
+    if pressure_kpa >= cast(AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA) {
+        AtmosphereZone::Troposphere
+    } else if pressure_kpa >= cast(AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA) {
+        AtmosphereZone::LowerStratosphere
+    } else if pressure_kpa >= pressure_at_altitude(cast(MESOSPHERE_BASE_M)) {
+        AtmosphereZone::UpperStratosphere
+    } else {
+        AtmosphereZone::Mesosphere
+    }
+}
+
+/// See [`crate::lapse_rate`].
+pub fn lapse_rate<F: Float>(zone: AtmosphereZone) -> F {
+    match zone {
+        AtmosphereZone::Troposphere => cast(-AltitudeCalculator::LAPSE_RATE),
+        AtmosphereZone::LowerStratosphere => F::zero(), // Isothermal layer
+        AtmosphereZone::UpperStratosphere => cast(AltitudeCalculator::UPPER_STRATOSPHERE_WARMING_RATE),
+        AtmosphereZone::Mesosphere => cast(-MESOSPHERE_LAYERS[1].2),
+    }
+}
+
+/// See [`crate::altitude_of_temperature`].
+pub fn altitude_of_temperature<F: Float>(target_c: F) -> Option<F> {
+    let altitude_m =
+        (cast::<F>(AltitudeCalculator::SEA_LEVEL_TEMPERATURE_C) - target_c) / cast(AltitudeCalculator::LAPSE_RATE);
+    let (bottom_m, top_m) = zone_bounds(AtmosphereZone::Troposphere);
+
+    // Also rejects NaN, which fails both comparisons
+    (altitude_m >= cast(bottom_m) && altitude_m <= cast(top_m)).then_some(altitude_m)
+}
+
+/// See [`crate::zone_boundary_pressure`].
+pub fn zone_boundary_pressure<F: Float>(zone: AtmosphereZone) -> (F, F) {
+    let (bottom_m, top_m) = zone_bounds(zone);
+
+    (pressure_at_altitude(cast(bottom_m)), pressure_at_altitude(cast(top_m)))
+}
+
+/// See [`crate::round_trip_error`].
+pub fn round_trip_error<F: Float>(altitude_m: F) -> F {
+    altitude_from_pressure(pressure_at_altitude(altitude_m)).map_or(F::nan(), |altitude| (altitude - altitude_m).abs())
+}
+
+/// See [`crate::altitudes_from_pressures`].
+pub fn altitudes_from_pressures<F: Float>(pressures_kpa: &[F], out: &mut [F]) -> Result<(), AltitudeError> {
+    if pressures_kpa.len() != out.len() {
+        return Err(AltitudeError::LengthMismatch);
+    }
+
+    for (index, (&pressure_kpa, altitude)) in pressures_kpa.iter().zip(out.iter_mut()).enumerate() {
+        *altitude = altitude_from_pressure(pressure_kpa).map_err(|_| AltitudeError::InvalidSample { index })?;
+    }
+
+    Ok(())
+}
+
+/// See [`crate::altitude_stream`].
+pub fn altitude_stream<F: Float, I: Iterator<Item = F>>(
+    pressures_kpa: I,
+) -> impl Iterator<Item = Result<F, AltitudeError>> {
+    pressures_kpa.map(altitude_from_pressure)
+}
+
+/// See [`crate::calculate_altitude_ft`].
+pub fn calculate_altitude_ft<F: Float>(
+    zone: AtmosphereZone,
+    temperature_c: F,
+    pressure_kpa: F,
+) -> Result<F, AltitudeError> {
+    calculate_altitude(zone, temperature_c, pressure_kpa).map(|altitude| altitude * cast(FEET_PER_METER))
+}
+
+/// Density in kg/m³ of dry air at a pressure in kPa and temperature in Celsius.
+fn ideal_gas_density<F: Float>(pressure_kpa: F, temperature_c: F) -> F {
+    let p = pressure_kpa * cast(1000.0); // Pressure in Pa
+    let t = temperature_c + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    p / (cast::<F>(DRY_AIR_GAS_CONSTANT) * t)
+}
+
+/// See [`crate::temperature_at_altitude`].
+pub fn temperature_at_altitude<F: Float>(altitude_m: F) -> F {
+    AltitudeCalculator::default().temperature_at_altitude(altitude_m)
+}
+
+/// See [`crate::pressure_at_altitude`].
+pub fn pressure_at_altitude<F: Float>(altitude_m: F) -> F {
+    AltitudeCalculator::default().pressure_at_altitude(altitude_m)
+}
+
+/// See [`crate::pressure_at_altitude_with_model`].
+pub fn pressure_at_altitude_with_model<F: Float>(altitude_m: F, model: AtmosphereModel) -> F {
+    AltitudeCalculator {
+        model,
+        ..AltitudeCalculator::default()
+    }
+    .pressure_at_altitude(altitude_m)
+}
+
+/// See [`crate::density_at_altitude`].
+pub fn density_at_altitude<F: Float>(altitude_m: F) -> F {
+    properties_at_altitude(altitude_m).density_kgm3
+}
+
+/// See [`crate::specific_weight`].
+pub fn specific_weight<F: Float>(altitude_m: F) -> F {
+    density_at_altitude(altitude_m) * cast(STANDARD_GRAVITY)
+}
+
+/// See [`crate::lifting_gas_density`].
+pub fn lifting_gas_density<F: Float>(stp_density_kgm3: F, altitude_m: F) -> F {
+    let properties = properties_at_altitude(altitude_m);
+    let kelvin = cast::<F>(KELVIN_OFFSET);
+
+    stp_density_kgm3 * properties.pressure_kpa / cast(101.325) * kelvin / (properties.temperature_c + kelvin)
+}
+
+/// See [`crate::buoyant_lift`].
+pub fn buoyant_lift<F: Float>(altitude_m: F, lifting_gas_density_kgm3: F) -> F {
+    (density_at_altitude(altitude_m) - lifting_gas_density_kgm3) * cast(STANDARD_GRAVITY)
+}
+
+/// See [`crate::properties_at_altitude`].
+pub fn properties_at_altitude<F: Float>(altitude_m: F) -> AtmosphereProperties<F> {
+    AltitudeCalculator::default().properties_at_altitude(altitude_m)
+}
+
+/// See [`crate::altitude_profile`].
+pub fn altitude_profile<F: Float>(start_m: F, end_m: F, step_m: F) -> impl Iterator<Item = AtmosphereProperties<F>> {
+    let step = if end_m < start_m { -step_m.abs() } else { step_m.abs() };
+
+    // A zero or non-finite step would never reach the end, so sample nothing
+    let samples = if step == F::zero() || !step.is_finite() {
+        0
+    } else {
+        ((end_m - start_m) / step).floor().to_usize().map_or(0, |n| n + 1)
+    };
+
+    (0..samples).map(move |i| properties_at_altitude(start_m + step * F::from(i).unwrap_or_else(F::nan)))
+}
+
+/// See [`crate::speed_of_sound`].
+pub fn speed_of_sound<F: Float>(altitude_m: F) -> F {
+    let t = temperature_at_altitude(altitude_m) + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    (cast::<F>(HEAT_CAPACITY_RATIO * DRY_AIR_GAS_CONSTANT) * t).sqrt()
+}
+
+/// See [`crate::mach_number`].
+pub fn mach_number<F: Float>(true_airspeed_mps: F, altitude_m: F) -> F {
+    let a = speed_of_sound(altitude_m);
+
+    if a.is_nan() || a <= F::zero() {
+        return F::nan(); // Altitude is outside the physical range of the model
+    }
+
+    true_airspeed_mps / a
+}
+
+/// See [`crate::impact_pressure`].
+pub fn impact_pressure<F: Float>(mach: F, altitude_m: F) -> F {
+    let p = pressure_at_altitude(altitude_m);
+    let gamma: F = cast(HEAT_CAPACITY_RATIO);
+    let exponent = gamma / (gamma - F::one());
+    let m2 = mach * mach;
+
+    if mach <= F::one() {
+        // Isentropic compression to rest
+        return p * ((F::one() + (gamma - F::one()) / cast(2.0) * m2).powf(exponent) - F::one());
+    }
+
+    // Rayleigh pitot formula, the pitot tube sits behind a normal shock
+    let shock = ((gamma + F::one()).powi(2) * m2 / (cast::<F>(4.0) * gamma * m2 - cast::<F>(2.0) * (gamma - F::one())))
+        .powf(exponent);
+    p * (shock * (F::one() - gamma + cast::<F>(2.0) * gamma * m2) / (gamma + F::one()) - F::one())
+}
+
+/// See [`crate::dynamic_viscosity`].
+pub fn dynamic_viscosity<F: Float>(altitude_m: F) -> F {
+    let t = temperature_at_altitude(altitude_m) + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    cast::<F>(1.458e-6) * t.powf(cast(1.5)) / (t + cast(110.4))
+}
+
+/// See [`crate::kinematic_viscosity`].
+pub fn kinematic_viscosity<F: Float>(altitude_m: F) -> F {
+    dynamic_viscosity(altitude_m) / density_at_altitude(altitude_m)
+}
+
+/// See [`crate::thermal_conductivity`].
+pub fn thermal_conductivity<F: Float>(altitude_m: F) -> F {
+    let t = temperature_at_altitude(altitude_m) + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    cast::<F>(2.64638e-3) * t.powf(cast(1.5)) / (t + cast::<F>(245.4) * cast::<F>(10.0).powf(cast::<F>(-12.0) / t))
+}
+
+/// See [`crate::prandtl_number`].
+pub fn prandtl_number<F: Float>(altitude_m: F) -> F {
+    dynamic_viscosity(altitude_m) * cast(AIR_SPECIFIC_HEAT) / thermal_conductivity(altitude_m)
+}
+
+/// See [`crate::potential_temperature`].
+pub fn potential_temperature<F: Float>(temperature_c: F, pressure_kpa: F) -> F {
+    let exponent = cast::<F>(POISSON_CONSTANT);
+
+    (temperature_c + cast(KELVIN_OFFSET)) * (cast::<F>(100.0) / pressure_kpa).powf(exponent)
+}
+
+/// See [`crate::reynolds_per_meter`].
+pub fn reynolds_per_meter<F: Float>(velocity_mps: F, altitude_m: F) -> F {
+    velocity_mps / kinematic_viscosity(altitude_m)
+}
+
+/// See [`crate::dynamic_pressure`].
+pub fn dynamic_pressure<F: Float>(true_airspeed_mps: F, altitude_m: F) -> F {
+    cast::<F>(0.5) * density_at_altitude(altitude_m) * true_airspeed_mps * true_airspeed_mps
+}
+
+/// See [`crate::hypsometric_altitude`].
+pub fn hypsometric_altitude<F: Float>(pressure_ref_kpa: F, pressure_kpa: F, mean_temp_c: F) -> F {
+    hypsometric_altitude_with_gravity(pressure_ref_kpa, pressure_kpa, mean_temp_c, cast(STANDARD_GRAVITY))
+}
+
+/// See [`crate::layer_mean_temperature`].
+pub fn layer_mean_temperature<F: Float>(alt_lower_m: F, alt_upper_m: F) -> F {
+    let (lower, upper) = if alt_upper_m < alt_lower_m {
+        (alt_upper_m, alt_lower_m)
+    } else {
+        (alt_lower_m, alt_upper_m)
+    };
+    if upper - lower <= F::zero() {
+        return temperature_at_altitude(lower);
+    }
+
+    // The integral of 1/T makes the mean consistent with the hypsometric equation
+    let (inverse, _) = inverse_temperature_integral(lower, upper);
+    (upper - lower) / inverse - cast(KELVIN_OFFSET)
+}
+
+/// Integral of 1/T of the model profile from 'lower' up to 'upper', in m/K,
+/// and the coldest temperature on the way in Kelvin.
+fn inverse_temperature_integral<F: Float>(lower: F, upper: F) -> (F, F) {
+    let kelvin = cast::<F>(KELVIN_OFFSET);
+
+    // The profile is linear between these, with a step at some of them
+    let breaks = [
+        11_000.0,
+        25_000.0,
+        MESOSPHERE_BASE_M,
+        MESOSPHERE_LAYERS[1].0,
+        MESOSPHERE_LAYERS[2].0,
+    ];
+    let inner = breaks.iter().map(|&b| cast::<F>(b)).filter(|&b| b > lower && b < upper);
+
+    let mut bottom = lower;
+    let mut inverse = F::zero();
+    let mut coldest = F::infinity();
+    for top in inner.chain(core::iter::once(upper)) {
+        let height = top - bottom;
+
+        // Extrapolate the ends from inside the segment so a step at its boundary does not leak in
+        let quarter = temperature_at_altitude(bottom + height * cast(0.25)) + kelvin;
+        let three_quarter = temperature_at_altitude(bottom + height * cast(0.75)) + kelvin;
+        let t_bottom = quarter * cast(1.5) - three_quarter * cast(0.5);
+        let t_top = three_quarter * cast(1.5) - quarter * cast(0.5);
+
+        inverse = inverse
+            + if (t_top - t_bottom).abs() < cast::<F>(1e-9) * quarter {
+                height / quarter // Isothermal segment
+            } else {
+                height * (t_top / t_bottom).ln() / (t_top - t_bottom)
+            };
+        coldest = coldest.min(t_bottom).min(t_top);
+        bottom = top;
+    }
+
+    (inverse, coldest)
+}
+
+/// See [`crate::hypsometric_altitude_with_gravity`].
+pub fn hypsometric_altitude_with_gravity<F: Float>(
+    pressure_ref_kpa: F,
+    pressure_kpa: F,
+    mean_temp_c: F,
+    gravity_mps2: F,
+) -> F {
+    let t = mean_temp_c + cast(KELVIN_OFFSET); // Mean layer temperature in Kelvin
+
+    cast::<F>(DRY_AIR_GAS_CONSTANT) * t / gravity_mps2 * (pressure_ref_kpa / pressure_kpa).ln()
+}
+
+/// See [`crate::altitude_from_pt`].
+pub fn altitude_from_pt<F: Float>(pressure_kpa: F, temperature_c: F) -> Result<F, AltitudeError> {
+    // Accept the same readings as the troposphere formula
+    calculate_altitude(AtmosphereZone::Troposphere, temperature_c, pressure_kpa)?;
+    let t = celsius_to_kelvin_checked(temperature_c)?;
+
+    Ok(column_altitude(
+        pressure_kpa,
+        t,
+        cast(AltitudeCalculator::SEA_LEVEL_PRESSURE_KPA),
+    ))
+}
+
+/// Height in meters of a pressure above a reference pressure, below air measured at 't' Kelvin.
+fn column_altitude<F: Float>(pressure_kpa: F, t: F, reference_kpa: F) -> F {
+    // The column below warms at the lapse rate, so its mean temperature is the reading plus half the lapse
+    // over the height. Solving the hypsometric equation for that height gives h = a·T / (1 - a·L/2).
+    let meters_per_kelvin = cast::<F>(DRY_AIR_GAS_CONSTANT / STANDARD_GRAVITY) * (reference_kpa / pressure_kpa).ln();
+
+    meters_per_kelvin * t / (F::one() - meters_per_kelvin * cast(AltitudeCalculator::LAPSE_RATE / 2.0))
+}
+
+/// See [`crate::altitude_best_effort`].
+pub fn altitude_best_effort<F: Float>(
+    pressure_kpa: F,
+    temperature_c: Option<F>,
+    qnh_hpa: Option<F>,
+) -> Result<F, AltitudeError> {
+    let qnh_kpa = qnh_hpa.map(|qnh| qnh / cast(10.0));
+
+    // The hypsometric column only covers the troposphere, outside it the temperature is left out
+    let kelvin = match temperature_c {
+        Some(temperature_c) => match altitude_from_pt(pressure_kpa, temperature_c) {
+            Err(AltitudeError::PressureTooLow { .. } | AltitudeError::PressureTooHigh { .. }) => {
+                celsius_to_kelvin_checked(temperature_c)?; // A faulty sensor is reported even where it is unused
+                None
+            }
+            result => {
+                result?;
+                Some(celsius_to_kelvin_checked(temperature_c)?)
+            }
+        },
+        None => None,
+    };
+
+    match (kelvin, qnh_kpa) {
+        (Some(kelvin), qnh_kpa) => {
+            let reference_kpa = match qnh_kpa {
+                Some(qnh_kpa) => altitude_from_pressure(qnh_kpa).map(|_| qnh_kpa)?,
+                None => cast(AltitudeCalculator::SEA_LEVEL_PRESSURE_KPA),
+            };
+
+            Ok(column_altitude(pressure_kpa, kelvin, reference_kpa))
+        }
+        // An altimeter set to the QNH reads the pressure altitude above the QNH level
+        (None, Some(qnh_kpa)) => Ok(altitude_from_pressure(pressure_kpa)? - altitude_from_pressure(qnh_kpa)?),
+        (None, None) => altitude_from_pressure(pressure_kpa),
+    }
+}
+
+/// See [`crate::calculate_altitude_isa_offset`].
+pub fn calculate_altitude_isa_offset<F: Float>(pressure_kpa: F, isa_offset_c: F) -> Result<F, AltitudeError> {
+    if !isa_offset_c.is_finite() {
+        return Err(AltitudeError::InvalidInput);
+    }
+    let pressure_altitude = altitude_from_pressure(pressure_kpa)?;
+
+    // Each slice of the column is thicker than standard by the ratio (T + offset) / T, which integrates to
+    // the pressure altitude plus the offset times the integral of 1/T
+    let (lower, upper) = if pressure_altitude < F::zero() {
+        (pressure_altitude, F::zero())
+    } else {
+        (F::zero(), pressure_altitude)
+    };
+    let (inverse, coldest_k) = inverse_temperature_integral(lower, upper);
+    celsius_to_kelvin_checked(coldest_k - cast(KELVIN_OFFSET) + isa_offset_c)?;
+
+    Ok(pressure_altitude + isa_offset_c * inverse.copysign(pressure_altitude))
+}
+
+/// See [`crate::pressure_ratio`].
+pub fn pressure_ratio<F: Float>(altitude_m: F) -> F {
+    pressure_at_altitude(altitude_m) / SeaLevelReference::default().pressure_kpa
+}
+
+/// See [`crate::temperature_ratio`].
+pub fn temperature_ratio<F: Float>(altitude_m: F) -> F {
+    let sea_level: SeaLevelReference<F> = SeaLevelReference::default();
+
+    (temperature_at_altitude(altitude_m) + cast(KELVIN_OFFSET)) / (sea_level.temperature_c + cast(KELVIN_OFFSET))
+}
+
+/// See [`crate::density_ratio`].
+pub fn density_ratio<F: Float>(altitude_m: F) -> F {
+    let sea_level: SeaLevelReference<F> = SeaLevelReference::default();
+
+    density_at_altitude(altitude_m) / ideal_gas_density(sea_level.pressure_kpa, sea_level.temperature_c)
+}
+
+/// See [`crate::tas_from_eas`].
+pub fn tas_from_eas<F: Float>(eas_mps: F, altitude_m: F) -> F {
+    eas_mps / density_ratio(altitude_m).sqrt()
+}
+
+/// See [`crate::eas_from_tas`].
+pub fn eas_from_tas<F: Float>(tas_mps: F, altitude_m: F) -> F {
+    tas_mps * density_ratio(altitude_m).sqrt()
+}
+
+/// See [`crate::vertical_speed`].
+pub fn vertical_speed<F: Float>(p1_kpa: F, t1_s: F, p2_kpa: F, t2_s: F) -> Result<F, AltitudeError> {
+    let dt = t2_s - t1_s;
+    if dt == F::zero() || !dt.is_finite() {
+        return Err(AltitudeError::InvalidInterval);
+    }
+
+    Ok((altitude_from_pressure(p2_kpa)? - altitude_from_pressure(p1_kpa)?) / dt)
+}
+
+/// See [`crate::pressure_exponential`].
+pub fn pressure_exponential<F: Float>(altitude_m: F) -> F {
+    let sea_level: SeaLevelReference<F> = SeaLevelReference::default();
+
+    sea_level.pressure_kpa * (-altitude_m / cast(EXPONENTIAL_SCALE_HEIGHT_M)).exp()
+}
+
+/// See [`crate::altitude_exponential`].
+pub fn altitude_exponential<F: Float>(pressure_kpa: F) -> F {
+    let sea_level: SeaLevelReference<F> = SeaLevelReference::default();
+
+    cast::<F>(EXPONENTIAL_SCALE_HEIGHT_M) * (sea_level.pressure_kpa / pressure_kpa).ln()
+}
+
+/// See [`crate::number_density`].
+pub fn number_density<F: Float>(altitude_m: F) -> F {
+    let properties = properties_at_altitude(altitude_m);
+    let p = properties.pressure_kpa * cast(1000.0); // Pressure in Pa
+    let t = properties.temperature_c + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    p / (cast::<F>(BOLTZMANN_CONSTANT) * t)
+}
+
+/// See [`crate::mean_free_path`].
+pub fn mean_free_path<F: Float>(altitude_m: F) -> F {
+    let cross_section =
+        core::f64::consts::SQRT_2 * core::f64::consts::PI * AIR_COLLISION_DIAMETER_M * AIR_COLLISION_DIAMETER_M;
+
+    number_density(altitude_m).recip() / cast(cross_section)
+}
+
+/// See [`crate::scale_height`].
+pub fn scale_height<F: Float>(altitude_m: F) -> F {
+    let t = temperature_at_altitude(altitude_m) + cast(KELVIN_OFFSET); // Temperature in Kelvin
+
+    cast::<F>(DRY_AIR_GAS_CONSTANT / STANDARD_GRAVITY) * t
+}
+
+/// See [`crate::oxygen_partial_pressure`].
+pub fn oxygen_partial_pressure<F: Float>(altitude_m: F) -> F {
+    cast::<F>(OXYGEN_FRACTION) * pressure_at_altitude(altitude_m)
+}
+
+/// See [`crate::is_hypoxia_risk`].
+pub fn is_hypoxia_risk<F: Float>(altitude_m: F) -> bool {
+    oxygen_partial_pressure(altitude_m) < oxygen_partial_pressure(cast(HYPOXIA_ALTITUDE_M))
+}
+
+/// See [`crate::time_of_useful_consciousness`].
+pub fn time_of_useful_consciousness<F: Float>(cabin_altitude_m: F) -> Option<F> {
+    let p = oxygen_partial_pressure(cabin_altitude_m);
+    let p_at = |altitude_m: f64| oxygen_partial_pressure::<F>(cast(altitude_m));
+
+    if p.is_nan() || p > p_at(USEFUL_CONSCIOUSNESS[0].0) {
+        return None; // Enough oxygen to stay conscious indefinitely, or no valid altitude
+    }
+
+    // Interpolate the logarithm of the time against the oxygen partial pressure, which drives the hypoxia
+    for pair in USEFUL_CONSCIOUSNESS.windows(2) {
+        let (above, below) = (p_at(pair[0].0), p_at(pair[1].0));
+
+        if p >= below {
+            let fraction = (above - p) / (above - below);
+            let (t0, t1) = (cast::<F>(pair[0].1).ln(), cast::<F>(pair[1].1).ln());
+
+            return Some((t0 + (t1 - t0) * fraction).exp());
+        }
+    }
+
+    Some(cast(USEFUL_CONSCIOUSNESS[USEFUL_CONSCIOUSNESS.len() - 1].1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f32_temperature_at_altitude() {
+        // Test the temperature profile evaluated in f32
+        assert!((temperature_at_altitude(0.0f32) - 15.04).abs() < 1e-4);
+        assert!((temperature_at_altitude(11000.0f32) - -56.35).abs() < 1e-3);
+        assert!((temperature_at_altitude(30000.0f32) - -41.51).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_f32_round_trip() {
+        // Converting an altitude to pressure and back in f32 should stay close to the f64 result
+        for &altitude_m in &[0.0f32, 1000.0, 5000.0, 10000.0, 12000.0, 18000.0, 24000.0] {
+            let zone = determine_zone(altitude_m);
+            let pressure_kpa = pressure_at_altitude(altitude_m);
+
+            let altitude = calculate_altitude(zone, 15.0f32, pressure_kpa).unwrap();
+
+            assert!((altitude - altitude_m).abs() < 5.0);
+        }
+    }
+
+    #[test]
+    fn test_f32_matches_f64() {
+        // The f32 path should agree with the f64 path within f32 precision
+        for &altitude_m in &[0.0, 5000.0, 15000.0, 30000.0] {
+            let density_f32 = density_at_altitude(altitude_m as f32);
+            let density_f64 = density_at_altitude(altitude_m);
+
+            assert!((f64::from(density_f32) - density_f64).abs() < density_f64 * 1e-4);
+
+            let mach_f32 = mach_number(250.0f32, altitude_m as f32);
+            let mach_f64 = mach_number(250.0, altitude_m);
+
+            assert!((f64::from(mach_f32) - mach_f64).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use num_traits::Float;
+
+use crate::density_at_altitude;
+
+/// Effective Earth radius used by the geopotential altitude relation, in meters.
+pub const EARTH_RADIUS_M: f64 = 6_356_766.0;
+
+/// Height measured in geopotential meters, as the model atmosphere uses.
+///
+/// A plain `f64` passed to the model functions is taken to be geopotential.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct GeopotentialAltitude(pub f64);
+
+/// True height above sea level in meters, as GPS reports it.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct GeometricAltitude(pub f64);
+
+impl From<f64> for GeopotentialAltitude {
+    fn from(h_geopotential_m: f64) -> Self {
+        GeopotentialAltitude(h_geopotential_m)
+    }
+}
+
+impl From<GeometricAltitude> for GeopotentialAltitude {
+    fn from(altitude: GeometricAltitude) -> Self {
+        GeopotentialAltitude(geometric_to_geopotential(altitude.0))
+    }
+}
+
+impl From<GeopotentialAltitude> for GeometricAltitude {
+    fn from(altitude: GeopotentialAltitude) -> Self {
+        GeometricAltitude(geopotential_to_geometric(altitude.0))
+    }
+}
+
+/// Convert geopotential altitude to geometric altitude.
+///
+/// Geometric altitude is the true height above sea level, as reported by GPS.
+///
+/// # Parameters
+/// - 'h_geopotential_m': Geopotential altitude in meters.
+///
+/// # Returns
+/// Geometric altitude in meters.
+pub fn geopotential_to_geometric(h_geopotential_m: f64) -> f64 {
+    EARTH_RADIUS_M * h_geopotential_m / (EARTH_RADIUS_M - h_geopotential_m)
+}
+
+/// Convert geometric altitude to geopotential altitude.
+///
+/// # Parameters
+/// - 'z_geometric_m': Geometric altitude in meters.
+///
+/// # Returns
+/// Geopotential altitude in meters.
+pub fn geometric_to_geopotential(z_geometric_m: f64) -> f64 {
+    EARTH_RADIUS_M * z_geometric_m / (EARTH_RADIUS_M + z_geometric_m)
+}
+
+/// Calculate model air density at a geometric altitude.
+///
+/// Converts to geopotential altitude first. Below a few kilometers the two
+/// altitudes differ by meters and so do the densities, by less than 0.1 %.
+///
+/// # Parameters
+/// - 'geometric_alt_m': Geometric altitude in meters, e.g. from GPS.
+///
+/// # Returns
+/// Density in kg/m³.
+pub fn density_from_geometric(geometric_alt_m: f64) -> f64 {
+    density_at_altitude(GeometricAltitude(geometric_alt_m))
+}
+
+/// Calculate the local acceleration of gravity.
+///
+/// Uses the WGS-84 normal gravity formula at sea level and the free-air
+/// reduction of 3.086e-6 m/s² per meter above it. Gravity grows by about 0.5 %
+/// from the equator to the poles.
+///
+/// # Parameters
+/// - 'latitude_deg': Geodetic latitude in degrees.
+/// - 'altitude_m': Geometric altitude in meters.
+///
+/// # Returns
+/// Acceleration of gravity in m/s².
+pub fn gravity(latitude_deg: f64, altitude_m: f64) -> f64 {
+    let sin2 = Float::powi(Float::sin(latitude_deg.to_radians()), 2);
+    let sea_level =
+        9.780_325_335_9 * (1.0 + 0.001_931_852_652_41 * sin2) / Float::sqrt(1.0 - 0.006_694_379_990_13 * sin2);
+
+    sea_level - 3.086e-6 * altitude_m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_geopotential_to_geometric() {
+        // Test against the published geometric height of the 11 km geopotential tropopause
+        assert!((geopotential_to_geometric(11000.0) - 11019.0).abs() < 1.0);
+
+        // The two heights agree at sea level and diverge by over 140 m at 30 km
+        assert_eq!(geopotential_to_geometric(0.0), 0.0);
+        assert!(geopotential_to_geometric(30000.0) - 30000.0 > 140.0);
+    }
+
+    #[test]
+    fn test_geometric_to_geopotential() {
+        // Test that the conversions invert each other
+        for &altitude_m in &[-400.0, 0.0, 11000.0, 30000.0] {
+            let round_trip = geometric_to_geopotential(geopotential_to_geometric(altitude_m));
+
+            assert!((round_trip - altitude_m).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_density_from_geometric() {
+        // Near the ground the altitude kind hardly matters
+        let low = density_from_geometric(100.0) / density_at_altitude(100.0) - 1.0;
+        assert!(low.abs() < 1e-5);
+
+        // At 30 km the geometric altitude is 141 m higher than its geopotential, and the air 2 % denser
+        let high = density_from_geometric(30000.0) / density_at_altitude(30000.0) - 1.0;
+        assert!(high > 0.01 && high < 0.03);
+    }
+
+    #[test]
+    fn test_altitude_newtypes() {
+        // Converting a GPS height to geopotential and back keeps it
+        for &altitude_m in &[-400.0, 0.0, 11000.0, 30000.0, 80000.0] {
+            let geopotential = GeopotentialAltitude::from(GeometricAltitude(altitude_m));
+            let round_trip = GeometricAltitude::from(geopotential);
+
+            assert!((round_trip.0 - altitude_m).abs() < 1.0);
+        }
+
+        // The model functions convert geometric heights, which sit lower in geopotential terms
+        let geometric = GeometricAltitude(30000.0);
+        assert_eq!(
+            crate::pressure_at_altitude(geometric),
+            crate::pressure_at_altitude(geometric_to_geopotential(30000.0))
+        );
+        assert!(crate::pressure_at_altitude(geometric) > crate::pressure_at_altitude(30000.0));
+    }
+
+    #[test]
+    fn test_gravity() {
+        // Test the WGS-84 values at the equator and the poles
+        assert!((gravity(0.0, 0.0) - 9.780).abs() < 0.001);
+        assert!((gravity(90.0, 0.0) - 9.832).abs() < 0.001);
+        assert!((gravity(-90.0, 0.0) - 9.832).abs() < 0.001);
+
+        // Gravity weakens with height
+        assert!((gravity(45.0, 0.0) - gravity(45.0, 10000.0) - 0.03086).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,123 @@
+use num_traits::Float;
+
+use crate::{DRY_AIR_GAS_CONSTANT, KELVIN_OFFSET};
+
+/// Ratio of the gas constants of dry air and water vapor.
+const VAPOR_MOLAR_RATIO: f64 = 0.622;
+
+/// Coefficients of the Magnus formula over water, dimensionless and in Celsius.
+const MAGNUS_B: f64 = 17.625;
+const MAGNUS_C: f64 = 243.04;
+
+/// Calculate the saturation vapor pressure over water.
+///
+/// Uses the Magnus formula with the coefficients of Alduchov and Eskridge,
+/// accurate to within 0.4 % between -40 °C and 50 °C. It overestimates towards
+/// the boiling point, by about 3 % at 100 °C.
+///
+/// # Parameters
+/// - 'temperature_c': Air temperature in Celsius.
+///
+/// # Returns
+/// Saturation vapor pressure in kPa.
+pub fn saturation_vapor_pressure(temperature_c: f64) -> f64 {
+    0.61094 * Float::exp(MAGNUS_B * temperature_c / (temperature_c + MAGNUS_C))
+}
+
+/// Calculate the dew point from temperature and relative humidity.
+///
+/// Uses the Magnus-Tetens approximation, which is accurate to a few tenths of a
+/// degree between -40 °C and 50 °C.
+///
+/// # Parameters
+/// - 'temperature_c': Air temperature in Celsius.
+/// - 'relative_humidity': Relative humidity in percent.
+///
+/// # Returns
+/// Dew point in Celsius, or NaN if the humidity is not above 0 % and at most 100 %.
+pub fn dew_point(temperature_c: f64, relative_humidity: f64) -> f64 {
+    if !(relative_humidity > 0.0 && relative_humidity <= 100.0) {
+        return f64::NAN; // Dry air has no dew point and supersaturated readings are invalid
+    }
+
+    let gamma = Float::ln(relative_humidity / 100.0) + MAGNUS_B * temperature_c / (MAGNUS_C + temperature_c);
+
+    MAGNUS_C * gamma / (MAGNUS_B - gamma)
+}
+
+/// Calculate the virtual temperature of moist air.
+///
+/// Dry air at the virtual temperature has the same density as the moist air, so
+/// it can be passed to the dry air formulas such as `hypsometric_altitude` to
+/// account for humidity.
+///
+/// # Parameters
+/// - 'temperature_c': Air temperature in Celsius.
+/// - 'relative_humidity': Relative humidity in percent.
+/// - 'pressure_kpa': Air pressure in kPa.
+///
+/// # Returns
+/// Virtual temperature in Celsius.
+pub fn virtual_temperature(temperature_c: f64, relative_humidity: f64, pressure_kpa: f64) -> f64 {
+    let vapor_pressure = relative_humidity / 100.0 * saturation_vapor_pressure(temperature_c);
+    let t = temperature_c + KELVIN_OFFSET; // Temperature in Kelvin
+
+    t / (1.0 - vapor_pressure / pressure_kpa * (1.0 - VAPOR_MOLAR_RATIO)) - KELVIN_OFFSET
+}
+
+/// Calculate the density of moist air.
+///
+/// # Parameters
+/// - 'pressure_kpa': Air pressure in kPa.
+/// - 'temperature_c': Air temperature in Celsius.
+/// - 'relative_humidity': Relative humidity in percent.
+///
+/// # Returns
+/// Density in kg/m³.
+pub fn moist_air_density(pressure_kpa: f64, temperature_c: f64, relative_humidity: f64) -> f64 {
+    let t = virtual_temperature(temperature_c, relative_humidity, pressure_kpa) + KELVIN_OFFSET; // Virtual temperature in Kelvin
+
+    pressure_kpa * 1000.0 / (DRY_AIR_GAS_CONSTANT * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saturation_vapor_pressure() {
+        // Test the freezing and boiling points of water
+        assert!((saturation_vapor_pressure(0.0) - 0.61094).abs() < 1e-9);
+        assert!((saturation_vapor_pressure(100.0) - 101.325).abs() < 0.04 * 101.325);
+        assert!(saturation_vapor_pressure(30.0) > saturation_vapor_pressure(20.0));
+    }
+
+    #[test]
+    fn test_virtual_temperature() {
+        // Dry air is unchanged
+        assert!((virtual_temperature(30.0, 0.0, 101.325) - 30.0).abs() < 1e-9);
+
+        // Saturated tropical air is a few kelvin warmer
+        let excess = virtual_temperature(30.0, 100.0, 101.325) - 30.0;
+        assert!(excess > 3.0 && excess < 6.0);
+    }
+
+    #[test]
+    fn test_dew_point() {
+        // Test against reference values
+        assert!((dew_point(20.0, 50.0) - 9.3).abs() < 0.1);
+        assert!((dew_point(30.0, 100.0) - 30.0).abs() < 1e-9);
+
+        // Humidities outside (0, 100] have no dew point
+        assert!(dew_point(20.0, 0.0).is_nan());
+        assert!(dew_point(20.0, 101.0).is_nan());
+        assert!(dew_point(20.0, f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_moist_air_density() {
+        // Humid air is lighter than dry air at the same pressure and temperature
+        assert!((moist_air_density(101.325, 15.0, 0.0) - 1.225).abs() < 0.001);
+        assert!(moist_air_density(101.325, 30.0, 100.0) < moist_air_density(101.325, 30.0, 0.0));
+    }
+}
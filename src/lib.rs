@@ -1,165 +1,2651 @@
+//! Altitude calculations based on NASA's 1960s atmospheric model.
+//!
+//! The model is formulated in geopotential altitude, so every altitude the
+//! model functions take or return is geopotential. Convert GPS (geometric)
+//! heights with [`geometric_to_geopotential`] first.
+//!
+//! The NASA model ends at the top of the stratosphere. Above 47 000 m the
+//! mesosphere layers of the US Standard Atmosphere 1976 are used.
+//!
+//! The crate is `no_std`. Floating point functions such as `powf`, `exp` and
+//! `ln` come from one of two features:
+//!
+//! - `std` (default): use the platform intrinsics provided by `std`.
+//! - `libm`: use the pure Rust `libm` crate, for bare-metal targets. Build with
+//!   `--no-default-features --features libm`.
+//!
+//! The optional `serde` feature derives `Serialize` and `Deserialize` for
+//! [`AtmosphereZone`].
+
 #![no_std]
 
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature must be enabled for floating point math");
+
 use core::f64;
+use core::fmt;
+use core::str;
+
+mod aviation;
+mod filter;
+mod format;
+pub mod generic;
+mod geopotential;
+mod humidity;
+mod sensor;
+mod table;
+mod units;
+
+pub use aviation::{
+    density_altitude, flight_level, flight_level_to_pressure_hpa, indicated_to_pressure_altitude, pressure_altitude,
+    pressure_to_altitude_ft, pressure_to_true_altitude, qnh_from_field, temperature_corrected_altitude,
+    STANDARD_PRESSURE_HPA,
+};
+pub use filter::{AltitudeFilter, PressureSmoother, ZoneTransitionDetector};
+pub use format::format_altitude;
+pub use geopotential::{
+    density_from_geometric, geometric_to_geopotential, geopotential_to_geometric, gravity, GeometricAltitude,
+    GeopotentialAltitude, EARTH_RADIUS_M,
+};
+pub use humidity::{dew_point, moist_air_density, saturation_vapor_pressure, virtual_temperature};
+pub use sensor::{altitude_from_adc, read_altitude, Barometer};
+pub use table::{lookup_altitude_from_pressure, lookup_interpolated, STANDARD_ATMOSPHERE_TABLE};
+pub use units::{to_celsius, to_kpa, AltitudeUnit, PressureUnit, TemperatureUnit};
+
+/// Specific gas constant for dry air in J/(kg·K).
+pub const DRY_AIR_GAS_CONSTANT: f64 = 287.058;
+
+/// Standard acceleration of gravity in m/s².
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// Ratio of specific heats for dry air.
+pub const HEAT_CAPACITY_RATIO: f64 = 1.4;
+
+/// Boltzmann constant in J/K.
+pub const BOLTZMANN_CONSTANT: f64 = 1.380649e-23;
+
+/// Offset between the Celsius and Kelvin scales.
+pub const KELVIN_OFFSET: f64 = 273.15;
+
+/// Number of feet in one meter.
+pub const FEET_PER_METER: f64 = 3.280839895;
+
+/// Number of meters in one international nautical mile.
+pub const METERS_PER_NAUTICAL_MILE: f64 = 1852.0;
+
+/// Density of helium at 0 °C and 101.325 kPa in kg/m³.
+pub const HELIUM_DENSITY_STP: f64 = 0.1786;
+
+/// Density of hydrogen at 0 °C and 101.325 kPa in kg/m³.
+pub const HYDROGEN_DENSITY_STP: f64 = 0.08988;
+
+/// Layers of the US Standard Atmosphere 1976 up to 84.852 km.
+///
+/// Each layer is its base geopotential altitude in m, base temperature in K,
+/// base pressure in Pa and lapse rate dT/dh in K/m, negative where the layer
+/// cools with height. [`AtmosphereModel::UsStandard1976`] is built on these.
+pub const LAYERS: &[(f64, f64, f64, f64)] = &generic::standard_layers();
 
 /// Atmospheric zones based on NASA's 1960s model.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Zones are ordered by increasing altitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AtmosphereZone {
     Troposphere,
     LowerStratosphere,
     UpperStratosphere,
+    /// Above 47 000 m, following the US Standard Atmosphere 1976.
+    ///
+    /// The 1976 temperatures are kept as tabulated, so the temperature drops
+    /// by about 11.8 K at 47 000 m from the warmer NASA upper stratosphere.
+    Mesosphere,
+}
+
+impl AtmosphereZone {
+    /// Every zone, in order of increasing altitude.
+    pub const fn all() -> &'static [AtmosphereZone] {
+        &[
+            AtmosphereZone::Troposphere,
+            AtmosphereZone::LowerStratosphere,
+            AtmosphereZone::UpperStratosphere,
+            AtmosphereZone::Mesosphere,
+        ]
+    }
+
+    /// Four-letter label for compact log lines.
+    pub const fn abbreviation(&self) -> &'static str {
+        match self {
+            AtmosphereZone::Troposphere => "TROP",
+            AtmosphereZone::LowerStratosphere => "LSTR",
+            AtmosphereZone::UpperStratosphere => "USTR",
+            AtmosphereZone::Mesosphere => "MESO",
+        }
+    }
+}
+
+impl fmt::Display for AtmosphereZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AtmosphereZone::Troposphere => "Troposphere",
+            AtmosphereZone::LowerStratosphere => "Lower Stratosphere",
+            AtmosphereZone::UpperStratosphere => "Upper Stratosphere",
+            AtmosphereZone::Mesosphere => "Mesosphere",
+        };
+
+        f.write_str(name)
+    }
+}
+
+impl str::FromStr for AtmosphereZone {
+    type Err = ParseZoneError;
+
+    /// Parse a zone name, ignoring case. Accepts the `Display` names and their
+    /// forms without a separator or with an underscore.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const NAMES: [(&str, AtmosphereZone); 8] = [
+            ("troposphere", AtmosphereZone::Troposphere),
+            ("lowerstratosphere", AtmosphereZone::LowerStratosphere),
+            ("lower_stratosphere", AtmosphereZone::LowerStratosphere),
+            ("lower stratosphere", AtmosphereZone::LowerStratosphere),
+            ("upperstratosphere", AtmosphereZone::UpperStratosphere),
+            ("upper_stratosphere", AtmosphereZone::UpperStratosphere),
+            ("upper stratosphere", AtmosphereZone::UpperStratosphere),
+            ("mesosphere", AtmosphereZone::Mesosphere),
+        ];
+
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+            .map(|&(_, zone)| zone)
+            .ok_or(ParseZoneError)
+    }
+}
+
+/// Error returned when a string does not name an atmospheric zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseZoneError;
+
+impl fmt::Display for ParseZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected one of troposphere, lower_stratosphere, upper_stratosphere or mesosphere")
+    }
+}
+
+impl core::error::Error for ParseZoneError {}
+
+/// Standard atmospheres the calculations can follow.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AtmosphereModel {
+    /// NASA's 1960s model, extended with the 1976 mesosphere.
+    #[default]
+    Nasa1960,
+    /// The US Standard Atmosphere 1976 up to 84 852 m.
+    UsStandard1976,
+}
+
+/// Planets whose atmosphere can be calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Planet {
+    /// Earth, following the selected [`AtmosphereModel`].
+    #[default]
+    Earth,
+    /// Mars, following NASA's simplified two-zone Mars model.
+    ///
+    /// [`AtmosphereZone::Troposphere`] selects the zone below 7000 m and any
+    /// higher zone the one above it.
+    Mars,
+}
+
+/// Handling of pressures outside the range of the requested zone.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BoundsPolicy {
+    /// Return [`AltitudeError::PressureTooHigh`] or [`AltitudeError::PressureTooLow`].
+    #[default]
+    Error,
+    /// Return the altitude of the zone boundary the pressure is beyond.
+    Clamp,
+}
+
+/// Model atmosphere at a single altitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphereProperties<F = f64> {
+    /// Atmospheric zone of the altitude.
+    pub zone: AtmosphereZone,
+    /// Temperature in Celsius.
+    pub temperature_c: F,
+    /// Pressure in kPa.
+    pub pressure_kpa: F,
+    /// Density in kg/m³.
+    pub density_kgm3: F,
+}
+
+impl<F: fmt::Display> fmt::Display for AtmosphereProperties<F> {
+    /// One line for logs, e.g. `TROP T=15.0C P=101.3kPa rho=1.225`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} T={:.1}C P={:.1}kPa rho={:.3}",
+            self.zone.abbreviation(),
+            self.temperature_c,
+            self.pressure_kpa,
+            self.density_kgm3
+        )
+    }
+}
+
+/// Sea-level conditions the troposphere is referenced to.
+/// 
+/// The default is the standard sea level of the NASA model. Substitute the day's
+/// actual QNH and temperature with `calculate_altitude_with_reference`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeaLevelReference<F = f64> {
+    /// Sea-level pressure in kPa.
+    pub pressure_kpa: F,
+    /// Sea-level temperature in Celsius.
+    pub temperature_c: F,
+}
+
+/// Atmosphere model with configurable troposphere parameters.
+/// 
+/// The default uses the NASA sea level and the model's lapse rate of 0.00649 K/m,
+/// slightly below the 0.0065 K/m of the ISA. Altitudes are returned in meters
+/// unless a different unit is set with [`AltitudeCalculator::builder`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltitudeCalculator<F = f64> {
+    reference: SeaLevelReference<F>,
+    lapse_rate_k_per_m: F,
+    output_unit: AltitudeUnit,
+    model: AtmosphereModel,
+    planet: Planet,
+    bounds: BoundsPolicy,
+}
+
+/// Step-by-step configuration of an [`AltitudeCalculator`].
+/// 
+/// Every setting starts at the value used by `AltitudeCalculator::default()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AltitudeCalculatorBuilder<F = f64> {
+    sea_level_pressure_kpa: F,
+    sea_level_temperature_c: F,
+    lapse_rate_k_per_m: F,
+    output_unit: AltitudeUnit,
+    model: AtmosphereModel,
+    planet: Planet,
+    bounds: BoundsPolicy,
+}
+
+impl AltitudeCalculator {
+    /// Sea-level temperature of the NASA model in Celsius.
+    pub const SEA_LEVEL_TEMPERATURE_C: f64 = 15.04;
+
+    /// Standard sea-level pressure in kPa.
+    pub const SEA_LEVEL_PRESSURE_KPA: f64 = 101.325;
+
+    /// Troposphere lapse rate of the NASA model in K/m.
+    pub const LAPSE_RATE: f64 = 0.00649;
+
+    /// Exponent of the troposphere pressure formula at [`Self::LAPSE_RATE`].
+    pub const TROPOSPHERE_EXPONENT: f64 = 5.256;
+
+    /// Pressure at 11 000 m in kPa, the standard troposphere evaluated at its top.
+    ///
+    /// The lower stratosphere starts from this pressure so the two zones meet
+    /// without a step.
+    pub const TROPOPAUSE_PRESSURE_KPA: f64 = 22.6971768530648;
+
+    /// Temperature of the isothermal lower stratosphere in Celsius, also
+    /// where the upper stratosphere starts at 25 000 m.
+    pub const STRATOPAUSE_TEMPERATURE_C: f64 = -56.46;
+
+    /// Exponential pressure decay of the lower stratosphere, per meter.
+    pub const LOWER_STRATOSPHERE_DECAY: f64 = 0.000157;
+
+    /// Pressure at 25 000 m in kPa, the lower stratosphere evaluated at its top.
+    pub const STRATOPAUSE_PRESSURE_KPA: f64 = 2.5199537521831594;
+
+    /// Temperature the upper-stratosphere profile extrapolates to at 0 m, in Celsius.
+    pub const UPPER_STRATOSPHERE_BASE_TEMPERATURE_C: f64 = -131.21;
+
+    /// Rate the upper stratosphere warms with height in K/m.
+    pub const UPPER_STRATOSPHERE_WARMING_RATE: f64 = 0.00299;
+
+    /// Exponent of the upper-stratosphere pressure formula.
+    pub const UPPER_STRATOSPHERE_EXPONENT: f64 = 11.388;
+}
+
+/// Reasons an altitude could not be calculated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AltitudeError {
+    /// Pressure is above the highest pressure the zone covers.
+    PressureTooHigh { limit_kpa: f64 },
+    /// Pressure is at or below the lowest pressure the zone covers.
+    PressureTooLow { limit_kpa: f64 },
+    /// Temperature or pressure is NaN or infinite.
+    InvalidInput,
+    /// The time between two samples is zero or not finite.
+    InvalidInterval,
+    /// A sample in a batch could not be converted, for any of the reasons above.
+    InvalidSample { index: usize },
+    /// Input and output slices of a batch have different lengths.
+    LengthMismatch,
+    /// Temperature is below absolute zero.
+    BelowAbsoluteZero,
+}
+
+impl fmt::Display for AltitudeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AltitudeError::PressureTooHigh { limit_kpa } => {
+                write!(f, "pressure is above the zone limit of {} kPa", limit_kpa)
+            }
+            AltitudeError::PressureTooLow { limit_kpa } => {
+                write!(f, "pressure is at or below the zone limit of {} kPa", limit_kpa)
+            }
+            AltitudeError::InvalidInput => write!(f, "temperature or pressure is not a finite number"),
+            AltitudeError::InvalidInterval => write!(f, "time between samples is zero or not finite"),
+            AltitudeError::InvalidSample { index } => write!(f, "sample {} could not be converted", index),
+            AltitudeError::LengthMismatch => write!(f, "input and output slices have different lengths"),
+            AltitudeError::BelowAbsoluteZero => write!(f, "temperature is below absolute zero"),
+        }
+    }
+}
+
+impl core::error::Error for AltitudeError {}
+
+/// Reasons a calculator configuration was rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigError {
+    /// A setting is NaN or infinite.
+    InvalidSetting,
+    /// Sea-level pressure is zero or negative.
+    NonPositivePressure,
+    /// Sea-level temperature is below absolute zero.
+    BelowAbsoluteZero,
+    /// Lapse rate is zero, negative or steeper than a real troposphere.
+    InvalidLapseRate,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidSetting => write!(f, "setting is not a finite number"),
+            ConfigError::NonPositivePressure => write!(f, "sea-level pressure is not positive"),
+            ConfigError::BelowAbsoluteZero => write!(f, "sea-level temperature is below absolute zero"),
+            ConfigError::InvalidLapseRate => write!(f, "lapse rate is not between 0 and 0.01 K/m"),
+        }
+    }
 }
 
+impl core::error::Error for ConfigError {}
+
     /// Determine atmosphere zone based on altitude.
     /// 
+    /// Altitudes below sea level are in the troposphere.
+    /// 
     /// # Parameters
-    /// - 'altitude_m': Altitude in meters.
+    /// - 'altitude_m': Geopotential altitude in meters.
     /// 
     /// # Returns
     /// Atmospheric zone.
-    pub fn determine_zone(altitude_m: f64) -> AtmosphereZone {
+    pub const fn determine_zone(altitude_m: f64) -> AtmosphereZone {
         if altitude_m <= 11000.0 {
             AtmosphereZone::Troposphere
-        } else if altitude_m <= 20000.0 {
+        } else if altitude_m <= 25000.0 {
             AtmosphereZone::LowerStratosphere
-        } else {
+        } else if altitude_m <= 47000.0 {
             AtmosphereZone::UpperStratosphere
+        } else {
+            AtmosphereZone::Mesosphere
+        }
+    }
+
+    /// Calculate altitude for a zone from temperature and pressure.
+    /// 
+    /// The troposphere accepts pressures down to 500 m below sea level.
+    /// 
+    /// The temperature is only checked to be a finite reading. Every zone finds
+    /// the altitude from pressure along the model temperature profile, so use
+    /// `altitude_from_pt` to correct for a measured temperature.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn calculate_altitude(zone: AtmosphereZone, temperature_c: f64, pressure_kpa: f64) -> Result<f64, AltitudeError> {
+        generic::calculate_altitude(zone, temperature_c, pressure_kpa)
+    }
+
+    /// Calculate altitude for a zone from temperature and pressure, relative to non-standard sea-level conditions.
+    /// 
+    /// The reference replaces the standard sea-level pressure and temperature in the
+    /// troposphere. The stratosphere zones keep their standard anchors.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// - 'reference': Sea-level pressure and temperature to measure from.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn calculate_altitude_with_reference(
+        zone: AtmosphereZone,
+        temperature_c: f64,
+        pressure_kpa: f64,
+        reference: SeaLevelReference,
+    ) -> Result<f64, AltitudeError> {
+        generic::calculate_altitude_with_reference(zone, temperature_c, pressure_kpa, reference)
+    }
+
+    /// Calculate altitude for a zone from temperature and pressure, following a chosen standard atmosphere.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// - 'model': Standard atmosphere to calculate with.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn calculate_altitude_with_model(
+        zone: AtmosphereZone,
+        temperature_c: f64,
+        pressure_kpa: f64,
+        model: AtmosphereModel,
+    ) -> Result<f64, AltitudeError> {
+        generic::calculate_altitude_with_model(zone, temperature_c, pressure_kpa, model)
+    }
+
+    /// Calculate altitude from pressure alone, using the model temperature profile.
+    /// 
+    /// Searches `pressure_at_altitude` across all zones, so no zone or temperature
+    /// reading is needed and the result is continuous across zone boundaries.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters between -500 m and 84852 m, or the reason it could not be calculated.
+    pub fn altitude_from_pressure(pressure_kpa: f64) -> Result<f64, AltitudeError> {
+        generic::altitude_from_pressure(pressure_kpa)
+    }
+
+    /// Measure how well `altitude_from_pressure` inverts `pressure_at_altitude`.
+    /// 
+    /// Downstream tests can assert this stays small. Sampling every meter from 0 m
+    /// to 30 000 m the worst case is about 2e-11 m, set by the bisection limit of
+    /// `f64` rather than by the zone formulas.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Absolute difference in meters between the altitude and its round trip through
+    /// pressure, or NaN if the altitude is outside the model range.
+    pub fn round_trip_error(altitude_m: f64) -> f64 {
+        generic::round_trip_error(altitude_m)
+    }
+
+    /// Calculate the standard altitude of a meteorological pressure level.
+    /// 
+    /// Same as `altitude_from_pressure` with the level in hPa, e.g. 500.0 for the
+    /// 500 hPa level. The NASA model puts the levels up to about 15 m above the
+    /// ICAO standard atmosphere heights.
+    /// 
+    /// # Parameters
+    /// - 'level_hpa': Pressure level in hPa.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or NaN if the level is outside the model.
+    pub fn altitude_of_pressure_level(level_hpa: f64) -> f64 {
+        generic::altitude_of_pressure_level(level_hpa)
+    }
+
+    /// Calculate altitude from pressure alone, along with the zone it lies in.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters and its atmospheric zone, or the reason it could not be calculated.
+    pub fn altitude_and_zone_from_pressure(pressure_kpa: f64) -> Result<(f64, AtmosphereZone), AltitudeError> {
+        generic::altitude_and_zone_from_pressure(pressure_kpa)
+    }
+
+    /// Calculate the altitude uncertainty caused by a pressure uncertainty.
+    /// 
+    /// Uses the slope of the model at the pressure, which is steeper in the thin
+    /// air higher up. Near sea level 0.012 kPa (0.12 hPa) is about 1 m.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// - 'pressure_sigma_kpa': Uncertainty of the pressure in kPa.
+    /// 
+    /// # Returns
+    /// Uncertainty of the altitude from `altitude_from_pressure` in meters, or NaN if the pressure is outside the model.
+    pub fn altitude_uncertainty(pressure_kpa: f64, pressure_sigma_kpa: f64) -> f64 {
+        generic::altitude_uncertainty(pressure_kpa, pressure_sigma_kpa)
+    }
+
+    /// Calculate the slope of altitude against pressure, d(altitude)/d(pressure).
+    /// 
+    /// Differentiates the inverse formula of the zone the pressure is in. The slope
+    /// is negative, as altitude rises when pressure falls, and steepens in the thin
+    /// air higher up.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Slope in m/kPa, about -84 at sea level, or NaN if the pressure is outside the model.
+    pub fn altitude_pressure_sensitivity(pressure_kpa: f64) -> f64 {
+        generic::altitude_pressure_sensitivity(pressure_kpa)
+    }
+
+    /// Determine the atmospheric zone a pressure belongs to.
+    /// 
+    /// Compares against the standard pressure at each zone boundary, so no
+    /// altitude has to be calculated. A boundary pressure belongs to the zone
+    /// below it, as in `determine_zone`.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Atmospheric zone of the standard atmosphere at that pressure.
+    pub fn zone_from_pressure(pressure_kpa: f64) -> AtmosphereZone {
+        generic::zone_from_pressure(pressure_kpa)
+    }
+
+    /// Get the rate temperature changes with height in a zone of the NASA model.
+    /// 
+    /// Positive rates warm with height, so the troposphere is -0.00649 K/m. The
+    /// mesosphere follows three layers of the US Standard Atmosphere 1976, and the
+    /// rate returned is that of its main layer from 51 km to 71 km.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone.
+    /// 
+    /// # Returns
+    /// Temperature gradient in K/m.
+    pub fn lapse_rate(zone: AtmosphereZone) -> f64 {
+        generic::lapse_rate(zone)
+    }
+
+    /// Find the altitude in the troposphere where the model reaches a temperature.
+    /// 
+    /// Useful for the freezing level. Above the troposphere the temperature holds
+    /// steady and then rises again, so colder targets have no single altitude.
+    /// 
+    /// # Parameters
+    /// - 'target_c': Temperature in Celsius.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or None if the troposphere never reaches the temperature.
+    pub fn altitude_of_temperature(target_c: f64) -> Option<f64> {
+        generic::altitude_of_temperature(target_c)
+    }
+
+    /// Find the US Standard Atmosphere 1976 layer an altitude is in.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Index into [`LAYERS`], 0 below sea level and 6 above the last base.
+    pub fn layer_for(altitude_m: f64) -> usize {
+        generic::layer_for(altitude_m)
+    }
+
+    /// Calculate the pressure limits of a zone in the standard atmosphere.
+    /// 
+    /// These are the limits `calculate_altitude` checks with the default settings.
+    /// The troposphere reaches down to 500 m below sea level and the mesosphere up
+    /// to 84 852 m.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone.
+    /// 
+    /// # Returns
+    /// Pressure at the bottom and at the top of the zone in kPa, the highest first.
+    pub fn zone_boundary_pressure(zone: AtmosphereZone) -> (f64, f64) {
+        generic::zone_boundary_pressure(zone)
+    }
+
+    /// Calculate altitudes for a batch of pressure readings.
+    /// 
+    /// Each reading is converted as by `altitude_from_pressure`, without allocating.
+    /// Conversion stops at the first reading that fails, leaving the altitudes of
+    /// the readings before it in 'out'.
+    /// 
+    /// # Parameters
+    /// - 'pressures_kpa': Pressures in kPa.
+    /// - 'out': Receives the geopotential altitude in meters of each pressure. Must be as long as 'pressures_kpa'.
+    /// 
+    /// # Returns
+    /// Nothing, or the index of the first reading that could not be converted.
+    pub fn altitudes_from_pressures(pressures_kpa: &[f64], out: &mut [f64]) -> Result<(), AltitudeError> {
+        generic::altitudes_from_pressures(pressures_kpa, out)
+    }
+
+    /// Convert a stream of pressure readings to altitudes as they arrive.
+    /// 
+    /// Each reading is converted as by `altitude_from_pressure` when the iterator
+    /// is advanced. A reading that fails yields its error and the stream goes on
+    /// with the next one.
+    /// 
+    /// # Parameters
+    /// - 'pressures_kpa': Pressures in kPa.
+    /// 
+    /// # Returns
+    /// Iterator over the geopotential altitude in meters of each reading, or the reason it could not be calculated.
+    pub fn altitude_stream<I: Iterator<Item = f64>>(pressures_kpa: I) -> impl Iterator<Item = Result<f64, AltitudeError>> {
+        generic::altitude_stream(pressures_kpa)
+    }
+
+    /// Calculate model temperature at an altitude.
+    /// 
+    /// The temperature steps down by about 11.8 K at 47 000 m, where the NASA
+    /// upper stratosphere meets the US Standard Atmosphere 1976 mesosphere.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Temperature in Celsius.
+    pub fn temperature_at_altitude(altitude_m: impl Into<GeopotentialAltitude>) -> f64 {
+        generic::temperature_at_altitude(altitude_m.into().0)
+    }
+
+    /// Calculate altitude in feet for a zone from temperature and pressure.
+    /// 
+    /// Same as `calculate_altitude`, with the result converted to feet.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in feet, or the reason it could not be calculated.
+    pub fn calculate_altitude_ft(zone: AtmosphereZone, temperature_c: f64, pressure_kpa: f64) -> Result<f64, AltitudeError> {
+        generic::calculate_altitude_ft(zone, temperature_c, pressure_kpa)
+    }
+
+    /// Calculate altitude for a zone from a temperature and a pressure in any unit.
+    /// 
+    /// Same as `calculate_altitude`, with the inputs converted to Celsius and kPa first.
+    /// 
+    /// # Parameters
+    /// - 'zone': Atmospheric zone the measurement was taken in.
+    /// - 'temperature': Temperature in 'temperature_unit'.
+    /// - 'temperature_unit': Unit of the temperature.
+    /// - 'pressure': Pressure in 'pressure_unit'.
+    /// - 'pressure_unit': Unit of the pressure.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn calculate_altitude_with_units(
+        zone: AtmosphereZone,
+        temperature: f64,
+        temperature_unit: TemperatureUnit,
+        pressure: f64,
+        pressure_unit: PressureUnit,
+    ) -> Result<f64, AltitudeError> {
+        calculate_altitude(zone, to_celsius(temperature, temperature_unit), to_kpa(pressure, pressure_unit))
+    }
+
+    /// Calculate model pressure at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Pressure in kPa.
+    pub fn pressure_at_altitude(altitude_m: impl Into<GeopotentialAltitude>) -> f64 {
+        generic::pressure_at_altitude(altitude_m.into().0)
+    }
+
+    /// Calculate model pressure at an altitude in hPa, the unit of altimeter settings.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Pressure in hPa.
+    pub fn pressure_at_altitude_hpa(altitude_m: impl Into<GeopotentialAltitude>) -> f64 {
+        pressure_at_altitude(altitude_m) * 10.0
+    }
+
+    /// Calculate pressure at an altitude, following a chosen standard atmosphere.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// - 'model': Standard atmosphere to calculate with.
+    /// 
+    /// # Returns
+    /// Pressure in kPa.
+    pub fn pressure_at_altitude_with_model(altitude_m: impl Into<GeopotentialAltitude>, model: AtmosphereModel) -> f64 {
+        generic::pressure_at_altitude_with_model(altitude_m.into().0, model)
+    }
+
+    /// Calculate model air density at an altitude using the ideal gas law.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Density in kg/m³.
+    pub fn density_at_altitude(altitude_m: impl Into<GeopotentialAltitude>) -> f64 {
+        generic::density_at_altitude(altitude_m.into().0)
+    }
+
+    /// Calculate the specific weight of air, its weight per unit volume.
+    /// 
+    /// Uses standard gravity, which matches geopotential altitude. For the weight
+    /// under local gravity multiply the density by [`gravity`] instead.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Specific weight in N/m³, about 12.01 at sea level.
+    pub fn specific_weight(altitude_m: impl Into<GeopotentialAltitude>) -> f64 {
+        generic::specific_weight(altitude_m.into().0)
+    }
+
+    /// Calculate the density of a lifting gas at the model conditions of an altitude.
+    /// 
+    /// Scales the density at 0 °C and 101.325 kPa with the model pressure and
+    /// temperature, treating the gas as ideal.
+    /// 
+    /// # Parameters
+    /// - 'stp_density_kgm3': Density of the gas at STP, e.g. [`HELIUM_DENSITY_STP`].
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Density of the gas in kg/m³.
+    pub fn lifting_gas_density(stp_density_kgm3: f64, altitude_m: f64) -> f64 {
+        generic::lifting_gas_density(stp_density_kgm3, altitude_m)
+    }
+
+    /// Calculate the net buoyant lift of a lifting gas per unit volume.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// - 'lifting_gas_density_kgm3': Density of the gas at the altitude, see [`lifting_gas_density`].
+    /// 
+    /// # Returns
+    /// Lift in N/m³, negative if the gas is heavier than the air.
+    pub fn buoyant_lift(altitude_m: f64, lifting_gas_density_kgm3: f64) -> f64 {
+        generic::buoyant_lift(altitude_m, lifting_gas_density_kgm3)
+    }
+
+    /// Calculate all model properties at an altitude at once.
+    /// 
+    /// Cheaper than calling the individual functions, which each classify the zone again.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters, or a `GeometricAltitude` to convert.
+    /// 
+    /// # Returns
+    /// Zone, temperature, pressure and density at the altitude.
+    pub fn properties_at_altitude(altitude_m: impl Into<GeopotentialAltitude>) -> AtmosphereProperties {
+        generic::properties_at_altitude(altitude_m.into().0)
+    }
+
+    /// Sample the model atmosphere at regular altitude intervals.
+    /// 
+    /// # Parameters
+    /// - 'start_m': First geopotential altitude in meters.
+    /// - 'end_m': Last geopotential altitude in meters, sampled if a whole number of steps from 'start_m'.
+    ///   May be below 'start_m' for a descending sweep.
+    /// - 'step_m': Distance between samples in meters. A zero step yields no samples.
+    /// 
+    /// # Returns
+    /// Iterator over the properties at each sampled altitude.
+    pub fn altitude_profile(start_m: f64, end_m: f64, step_m: f64) -> impl Iterator<Item = AtmosphereProperties> {
+        generic::altitude_profile(start_m, end_m, step_m)
+    }
+
+    /// Calculate the speed of sound at an altitude.
+    /// 
+    /// The value assumes dry air, so humidity is not taken into account.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Speed of sound in m/s.
+    pub fn speed_of_sound(altitude_m: f64) -> f64 {
+        generic::speed_of_sound(altitude_m)
+    }
+
+    /// Calculate the Mach number for a true airspeed at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'true_airspeed_mps': True airspeed in m/s.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Mach number, or NaN if the altitude gives no positive speed of sound.
+    pub fn mach_number(true_airspeed_mps: f64, altitude_m: f64) -> f64 {
+        generic::mach_number(true_airspeed_mps, altitude_m)
+    }
+
+    /// Calculate the impact pressure a pitot tube measures at a Mach number.
+    /// 
+    /// Uses isentropic compression up to Mach 1 and the Rayleigh pitot formula
+    /// above it, where a normal shock forms ahead of the tube.
+    /// 
+    /// # Parameters
+    /// - 'mach': Mach number.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Total minus static pressure in kPa.
+    pub fn impact_pressure(mach: f64, altitude_m: f64) -> f64 {
+        generic::impact_pressure(mach, altitude_m)
+    }
+
+    /// Calculate the dynamic viscosity of air at an altitude.
+    /// 
+    /// Uses Sutherland's law, which only depends on temperature.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Dynamic viscosity in Pa·s.
+    pub fn dynamic_viscosity(altitude_m: f64) -> f64 {
+        generic::dynamic_viscosity(altitude_m)
+    }
+
+    /// Calculate the kinematic viscosity of air at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Kinematic viscosity in m²/s.
+    pub fn kinematic_viscosity(altitude_m: f64) -> f64 {
+        generic::kinematic_viscosity(altitude_m)
+    }
+
+    /// Calculate the thermal conductivity of air at an altitude.
+    /// 
+    /// Uses the correlation of the US Standard Atmosphere 1976, which only
+    /// depends on temperature.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Thermal conductivity in W/(m·K).
+    pub fn thermal_conductivity(altitude_m: f64) -> f64 {
+        generic::thermal_conductivity(altitude_m)
+    }
+
+    /// Calculate the Prandtl number of air at an altitude.
+    /// 
+    /// Ratio of momentum to thermal diffusivity, from the dynamic viscosity,
+    /// a specific heat of 1005 J/(kg·K) and the thermal conductivity.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Prandtl number, dimensionless.
+    pub fn prandtl_number(altitude_m: f64) -> f64 {
+        generic::prandtl_number(altitude_m)
+    }
+
+    /// Calculate the potential temperature of air.
+    /// 
+    /// This is the temperature the air would reach if brought dry-adiabatically
+    /// to 100 kPa. Uses the Poisson constant R/cp of 0.2854 for dry air.
+    /// 
+    /// # Parameters
+    /// - 'temperature_c': Temperature in Celsius.
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Potential temperature in Kelvin.
+    pub fn potential_temperature(temperature_c: f64, pressure_kpa: f64) -> f64 {
+        generic::potential_temperature(temperature_c, pressure_kpa)
+    }
+
+    /// Calculate the Reynolds number per meter of characteristic length.
+    /// 
+    /// Multiply by a chord or diameter in meters to get the Reynolds number.
+    /// 
+    /// # Parameters
+    /// - 'velocity_mps': Airspeed in m/s.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Reynolds number per meter.
+    pub fn reynolds_per_meter(velocity_mps: f64, altitude_m: f64) -> f64 {
+        generic::reynolds_per_meter(velocity_mps, altitude_m)
+    }
+
+    /// Calculate the dynamic pressure of a true airspeed at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'true_airspeed_mps': True airspeed in m/s.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Dynamic pressure in Pa.
+    pub fn dynamic_pressure(true_airspeed_mps: f64, altitude_m: f64) -> f64 {
+        generic::dynamic_pressure(true_airspeed_mps, altitude_m)
+    }
+
+    /// Calculate the mean model temperature of a layer for the hypsometric equation.
+    /// 
+    /// Integrates the model profile analytically over each linear segment, split at
+    /// the zone boundaries. The mean is weighted by the pressure decay of the air,
+    /// so `hypsometric_altitude` across the layer gives back its thickness.
+    /// 
+    /// # Parameters
+    /// - 'alt_lower_m': Geopotential altitude of the bottom of the layer in meters.
+    /// - 'alt_upper_m': Geopotential altitude of the top of the layer in meters.
+    /// 
+    /// # Returns
+    /// Mean temperature in Celsius.
+    pub fn layer_mean_temperature(alt_lower_m: f64, alt_upper_m: f64) -> f64 {
+        generic::layer_mean_temperature(alt_lower_m, alt_upper_m)
+    }
+
+    /// Calculate the thickness of a layer from the hypsometric equation.
+    /// 
+    /// Unlike the model profile this uses a measured mean temperature, so it stays
+    /// accurate on days that are warmer or colder than standard. Pass a
+    /// `virtual_temperature` to account for humidity.
+    /// 
+    /// # Parameters
+    /// - 'pressure_ref_kpa': Pressure at the bottom of the layer in kPa.
+    /// - 'pressure_kpa': Pressure at the top of the layer in kPa.
+    /// - 'mean_temp_c': Mean temperature of the layer in Celsius.
+    /// 
+    /// # Returns
+    /// Height of the top above the bottom of the layer in meters.
+    pub fn hypsometric_altitude(pressure_ref_kpa: f64, pressure_kpa: f64, mean_temp_c: f64) -> f64 {
+        generic::hypsometric_altitude(pressure_ref_kpa, pressure_kpa, mean_temp_c)
+    }
+
+    /// Calculate altitude from pressure on a day offset from the standard atmosphere, such as ISA+15.
+    /// 
+    /// Shifts the whole model temperature profile by the offset and integrates the
+    /// hypsometric equation up to the pressure altitude. An offset of zero gives
+    /// the pressure altitude itself.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// - 'isa_offset_c': Difference of the day from the standard temperature in Celsius.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn calculate_altitude_isa_offset(pressure_kpa: f64, isa_offset_c: f64) -> Result<f64, AltitudeError> {
+        generic::calculate_altitude_isa_offset(pressure_kpa, isa_offset_c)
+    }
+
+    /// Calculate altitude from a pressure and the temperature measured with it.
+    /// 
+    /// Applies the hypsometric equation from standard sea-level pressure, assuming
+    /// the air below warms at the model lapse rate from the measured temperature.
+    /// Warmer than standard air is less dense, so the same pressure is reached
+    /// higher up.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa, within the troposphere.
+    /// - 'temperature_c': Temperature measured at the same point in Celsius.
+    /// 
+    /// # Returns
+    /// Geopotential altitude in meters, or the reason it could not be calculated.
+    pub fn altitude_from_pt(pressure_kpa: f64, temperature_c: f64) -> Result<f64, AltitudeError> {
+        generic::altitude_from_pt(pressure_kpa, temperature_c)
+    }
+
+    /// Calculate altitude from a pressure with whichever other readings are available.
+    /// 
+    /// The most accurate method the inputs allow is used:
+    /// 1. With a temperature and a pressure in the troposphere, the hypsometric
+    ///    equation as in `altitude_from_pt`, measured up from the QNH if there is
+    ///    one and from standard sea-level pressure otherwise. Outside the
+    ///    troposphere the temperature is only checked and the next method applies.
+    /// 2. With only a QNH, the pressure altitude above the QNH level, which is what
+    ///    an altimeter set to it reads.
+    /// 3. With the pressure alone, `altitude_from_pressure`.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// - 'temperature_c': Temperature measured at the same point in Celsius, if known.
+    /// - 'qnh_hpa': Local altimeter setting in hPa, if known.
+    /// 
+    /// # Returns
+    /// Altitude in meters, or the reason it could not be calculated.
+    pub fn altitude_best_effort(
+        pressure_kpa: f64,
+        temperature_c: Option<f64>,
+        qnh_hpa: Option<f64>,
+    ) -> Result<f64, AltitudeError> {
+        generic::altitude_best_effort(pressure_kpa, temperature_c, qnh_hpa)
+    }
+
+    /// Calculate the thickness of a layer from the hypsometric equation with local gravity.
+    /// 
+    /// With the gravity from `gravity` the result is a geometric rather than a
+    /// geopotential thickness. Gravity varies by about 0.5 % between the equator and
+    /// the poles, which shifts the thickness by up to 5 m per kilometer of layer.
+    /// 
+    /// # Parameters
+    /// - 'pressure_ref_kpa': Pressure at the bottom of the layer in kPa.
+    /// - 'pressure_kpa': Pressure at the top of the layer in kPa.
+    /// - 'mean_temp_c': Mean temperature of the layer in Celsius.
+    /// - 'gravity_mps2': Mean acceleration of gravity over the layer in m/s².
+    /// 
+    /// # Returns
+    /// Height of the top above the bottom of the layer in meters.
+    pub fn hypsometric_altitude_with_gravity(pressure_ref_kpa: f64, pressure_kpa: f64, mean_temp_c: f64, gravity_mps2: f64) -> f64 {
+        generic::hypsometric_altitude_with_gravity(pressure_ref_kpa, pressure_kpa, mean_temp_c, gravity_mps2)
+    }
+
+    /// Calculate the pressure ratio delta, the pressure relative to standard sea level.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Pressure ratio, 1.0 at sea level.
+    pub fn pressure_ratio(altitude_m: f64) -> f64 {
+        generic::pressure_ratio(altitude_m)
+    }
+
+    /// Calculate the temperature ratio theta, the absolute temperature relative to standard sea level.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Temperature ratio, 1.0 at sea level.
+    pub fn temperature_ratio(altitude_m: f64) -> f64 {
+        generic::temperature_ratio(altitude_m)
+    }
+
+    /// Calculate the density ratio sigma, the density relative to standard sea level.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Density ratio, 1.0 at sea level.
+    pub fn density_ratio(altitude_m: f64) -> f64 {
+        generic::density_ratio(altitude_m)
+    }
+
+    /// Convert equivalent airspeed to true airspeed at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'eas_mps': Equivalent airspeed in m/s.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// True airspeed in m/s.
+    pub fn tas_from_eas(eas_mps: f64, altitude_m: f64) -> f64 {
+        generic::tas_from_eas(eas_mps, altitude_m)
+    }
+
+    /// Convert true airspeed to equivalent airspeed at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'tas_mps': True airspeed in m/s.
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Equivalent airspeed in m/s.
+    pub fn eas_from_tas(tas_mps: f64, altitude_m: f64) -> f64 {
+        generic::eas_from_tas(tas_mps, altitude_m)
+    }
+
+    /// Calculate the vertical speed between two timed pressure samples.
+    /// 
+    /// Both pressures are converted with `altitude_from_pressure`, so the samples may
+    /// lie in different zones.
+    /// 
+    /// # Parameters
+    /// - 'p1_kpa': First pressure in kPa.
+    /// - 't1_s': Time of the first sample in seconds.
+    /// - 'p2_kpa': Second pressure in kPa.
+    /// - 't2_s': Time of the second sample in seconds.
+    /// 
+    /// # Returns
+    /// Vertical speed in m/s, positive when climbing, or the reason it could not be calculated.
+    pub fn vertical_speed(p1_kpa: f64, t1_s: f64, p2_kpa: f64, t2_s: f64) -> Result<f64, AltitudeError> {
+        generic::vertical_speed(p1_kpa, t1_s, p2_kpa, t2_s)
+    }
+
+    /// Estimate pressure at an altitude with a single exponential.
+    /// 
+    /// Treats the whole atmosphere as isothermal with a scale height of 8500 m. This
+    /// costs one `exp` instead of the zone formulas, but is only within a few percent
+    /// of the model in the lower troposphere and drifts further with altitude.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Approximate pressure in kPa.
+    pub fn pressure_exponential(altitude_m: f64) -> f64 {
+        generic::pressure_exponential(altitude_m)
+    }
+
+    /// Estimate altitude from pressure with a single exponential.
+    /// 
+    /// The inverse of `pressure_exponential`, costing one `ln`, with the same loss of accuracy.
+    /// 
+    /// # Parameters
+    /// - 'pressure_kpa': Pressure in kPa.
+    /// 
+    /// # Returns
+    /// Approximate geopotential altitude in meters.
+    pub fn altitude_exponential(pressure_kpa: f64) -> f64 {
+        generic::altitude_exponential(pressure_kpa)
+    }
+
+    /// Calculate the number of air molecules per cubic meter at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Number density in molecules/m³.
+    pub fn number_density(altitude_m: f64) -> f64 {
+        generic::number_density(altitude_m)
+    }
+
+    /// Calculate the mean free path of air molecules at an altitude.
+    /// 
+    /// Uses an effective collision diameter of 3.62e-10 m for air.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Mean free path in meters.
+    pub fn mean_free_path(altitude_m: f64) -> f64 {
+        generic::mean_free_path(altitude_m)
+    }
+
+    /// Calculate the local pressure scale height at an altitude.
+    /// 
+    /// The height over which pressure falls by a factor of e in air at the local temperature.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Scale height in meters.
+    pub fn scale_height(altitude_m: f64) -> f64 {
+        generic::scale_height(altitude_m)
+    }
+
+    /// Calculate the partial pressure of oxygen at an altitude.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// Oxygen partial pressure in kPa.
+    pub fn oxygen_partial_pressure(altitude_m: f64) -> f64 {
+        generic::oxygen_partial_pressure(altitude_m)
+    }
+
+    /// Check whether breathing unpressurized air at an altitude risks hypoxia.
+    /// 
+    /// The threshold is the oxygen partial pressure at 3000 m, about 14.7 kPa, the
+    /// usual limit for cabin altitude without supplemental oxygen.
+    /// 
+    /// # Parameters
+    /// - 'altitude_m': Geopotential altitude in meters.
+    /// 
+    /// # Returns
+    /// True if the oxygen partial pressure is below the threshold.
+    pub fn is_hypoxia_risk(altitude_m: f64) -> bool {
+        generic::is_hypoxia_risk(altitude_m)
+    }
+
+    /// Estimate the time of useful consciousness after losing cabin pressure.
+    /// 
+    /// Interpolates the FAA table from about 25 minutes at 5500 m down to
+    /// 6 to 9 seconds above 15 000 m, against the oxygen partial pressure.
+    /// Individuals vary widely, so treat the result as a rough guide only.
+    /// 
+    /// # Parameters
+    /// - 'cabin_altitude_m': Geopotential cabin altitude in meters.
+    /// 
+    /// # Returns
+    /// Time in seconds, or None below 5486 m (FL180) where it is effectively unlimited.
+    pub fn time_of_useful_consciousness(cabin_altitude_m: f64) -> Option<f64> {
+        generic::time_of_useful_consciousness(cabin_altitude_m)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+    
+        #[test]
+        fn test_determine_zone() {
+            // Test determining the atmospheric zone based on altitude
+            assert_eq!(determine_zone(5000.0), AtmosphereZone::Troposphere); // Altitude within Troposphere
+            assert_eq!(determine_zone(15000.0), AtmosphereZone::LowerStratosphere); // Altitude within Lower Stratosphere
+            assert_eq!(determine_zone(30000.0), AtmosphereZone::UpperStratosphere); // Altitude within Upper Stratosphere
+        }
+    
+        #[test]
+        fn test_calculate_altitude_troposphere() {
+            // Test altitude calculation within the Troposphere
+            let zone = AtmosphereZone::Troposphere;
+            let temperature_c = 10.0; // Example temperature in Celsius
+            let pressure_kpa = 90.0; // Example pressure in kPa
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Check if the function returns some altitude value
+            assert!(altitude.is_ok());
+    
+            // Verify the calculated altitude is close to an expected range (within ±50 m of 1000 m)
+            assert!((altitude.unwrap() - 1000.0).abs() < 50.0);
+        }
+    
+        #[test]
+        fn test_calculate_altitude_lower_stratosphere() {
+            // Test altitude calculation within the Lower Stratosphere
+            let zone = AtmosphereZone::LowerStratosphere;
+            let temperature_c = -56.5; // Constant temperature in Celsius for this zone
+            let pressure_kpa = 20.0; // Example pressure in kPa
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Check if the function returns some altitude value
+            assert!(altitude.is_ok());
+    
+            // Verify the calculated altitude is close to an expected range (within ±500 m of 12000 m)
+            assert!((altitude.unwrap() - 12000.0).abs() < 500.0);
+        }
+    
+        #[test]
+        fn test_calculate_altitude_upper_stratosphere() {
+            // Test altitude calculation within the Upper Stratosphere
+            let zone = AtmosphereZone::UpperStratosphere;
+            let temperature_c = -41.5; // Example temperature in Celsius
+            let pressure_kpa = 1.17; // Example pressure in kPa
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Check if the function returns some altitude value
+            assert!(altitude.is_ok());
+    
+            // Verify the calculated altitude is close to an expected range (within ±100 m of 30000 m)
+            assert!((altitude.unwrap() - 30000.0).abs() < 100.0);
+        }
+    
+        #[test]
+        fn test_invalid_pressure() {
+            // Test invalid pressure for the Troposphere
+            let zone = AtmosphereZone::Troposphere;
+            let temperature_c = 10.0; // Example temperature in Celsius
+            let pressure_kpa = 200.0; // Pressure too high for the Troposphere
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Ensure the function returns an error for invalid input
+            assert!(matches!(altitude, Err(AltitudeError::PressureTooHigh { .. })));
+        }
+    
+        #[test]
+        fn test_invalid_zone_pressure() {
+            // Test invalid pressure for the Lower Stratosphere
+            let zone = AtmosphereZone::LowerStratosphere;
+            let temperature_c = -56.5; // Constant temperature in Celsius for this zone
+            let pressure_kpa = 100.0; // Pressure too high for the Lower Stratosphere
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Ensure the function returns an error for invalid input
+            assert!(matches!(altitude, Err(AltitudeError::PressureTooHigh { .. })));
+        }
+    
+        #[test]
+        fn test_pressure_too_low() {
+            // Test pressure below the bottom of the Troposphere
+            let zone = AtmosphereZone::Troposphere;
+            let temperature_c = -50.0; // Example temperature in Celsius
+            let pressure_kpa = 20.0; // Pressure too low for the Troposphere
+    
+            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+    
+            // Ensure the error reports the violated limit, the tropopause pressure of the troposphere formula
+            let limit = pressure_at_altitude(11000.0);
+            match altitude {
+                Err(AltitudeError::PressureTooLow { limit_kpa }) => assert!((limit_kpa - limit).abs() < 1e-12),
+                result => panic!("unexpected {:?}", result),
+            }
+        }
+    
+        #[test]
+        fn test_nan_input() {
+            // Test that NaN inputs are rejected instead of producing NaN altitude
+            let zone = AtmosphereZone::Troposphere;
+    
+            assert_eq!(calculate_altitude(zone, f64::NAN, 90.0), Err(AltitudeError::InvalidInput));
+            assert_eq!(calculate_altitude(zone, 10.0, f64::NAN), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_infinite_input() {
+            // Test that infinite inputs are rejected in every zone
+            let zones = [
+                AtmosphereZone::Troposphere,
+                AtmosphereZone::LowerStratosphere,
+                AtmosphereZone::UpperStratosphere,
+                AtmosphereZone::Mesosphere,
+            ];
+            for zone in zones {
+                assert_eq!(calculate_altitude(zone, f64::INFINITY, 10.0), Err(AltitudeError::InvalidInput));
+                assert_eq!(calculate_altitude(zone, -40.0, f64::INFINITY), Err(AltitudeError::InvalidInput));
+                assert_eq!(calculate_altitude(zone, f64::NEG_INFINITY, 10.0), Err(AltitudeError::InvalidInput));
+            }
+    
+            assert_eq!(altitude_from_pressure(f64::INFINITY), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_determine_zone_stratosphere_boundary() {
+            // The lower stratosphere extends up to 25000 m, where the upper stratosphere formulas are anchored
+            assert_eq!(determine_zone(20001.0), AtmosphereZone::LowerStratosphere);
+            assert_eq!(determine_zone(24999.0), AtmosphereZone::LowerStratosphere);
+            assert_eq!(determine_zone(25001.0), AtmosphereZone::UpperStratosphere);
+        }
+    
+        #[test]
+        fn test_pressure_at_altitude() {
+            // Test model pressure at sea level and at the tropopause
+            assert!((pressure_at_altitude(0.0) - 101.3).abs() < 0.2);
+            assert!((pressure_at_altitude(11000.0) - 22.65).abs() < 0.1);
+    
+            // Pressure falls with altitude
+            assert!(pressure_at_altitude(30000.0) < pressure_at_altitude(20000.0));
+        }
+    
+        #[test]
+        fn test_pressure_at_altitude_round_trip() {
+            // Converting an altitude to pressure and back should recover the altitude
+            for &altitude_m in &[0.0, 1000.0, 5000.0, 10000.0, 12000.0, 18000.0, 24000.0, 26000.0, 30000.0] {
+                let zone = determine_zone(altitude_m);
+                let pressure_kpa = pressure_at_altitude(altitude_m);
+    
+                let altitude = calculate_altitude(zone, 15.0, pressure_kpa).unwrap();
+    
+                assert!((altitude - altitude_m).abs() < 1.0);
+            }
+        }
+    
+        #[test]
+        fn test_temperature_at_altitude() {
+            // Test the temperature profile in each zone
+            assert!((temperature_at_altitude(0.0) - 15.04).abs() < 1e-9); // Sea level
+            assert!((temperature_at_altitude(11000.0) - -56.4).abs() < 0.1); // Tropopause
+            assert!((temperature_at_altitude(15000.0) - -56.46).abs() < 1e-9); // Isothermal lower stratosphere
+            assert!((temperature_at_altitude(30000.0) - -41.51).abs() < 1e-9); // Warming upper stratosphere
+        }
+    
+        #[test]
+        fn test_density_at_altitude() {
+            // Sea level density should be within 1% of the standard 1.225 kg/m³
+            assert!((density_at_altitude(0.0) - 1.225).abs() < 1.225 * 0.01);
+    
+            // Density falls with altitude
+            assert!(density_at_altitude(10000.0) < density_at_altitude(0.0));
+        }
+    
+        #[test]
+        fn test_speed_of_sound() {
+            // Test the speed of sound at sea level and at the tropopause
+            assert!((speed_of_sound(0.0) - 340.3).abs() < 0.5);
+            assert!((speed_of_sound(11000.0) - 295.0).abs() < 0.5);
+        }
+    
+        #[test]
+        fn test_mach_number() {
+            // 250 m/s at 10000 m, where the speed of sound is about 299.6 m/s
+            assert!((mach_number(250.0, 10000.0) - 0.8346).abs() < 0.001);
+    
+            // A bad altitude reading has no speed of sound
+            assert!(mach_number(250.0, f64::NAN).is_nan());
+        }
+    
+        #[test]
+        fn test_calculate_altitude_ft() {
+            // Test that 1000 m of altitude is reported as about 3280.84 ft
+            let zone = AtmosphereZone::Troposphere;
+            let pressure_kpa = pressure_at_altitude(1000.0);
+    
+            let altitude = calculate_altitude_ft(zone, 8.55, pressure_kpa);
+    
+            assert!((altitude.unwrap() - 3280.84).abs() < 0.1);
+    
+            // Errors pass through unchanged
+            assert!(matches!(calculate_altitude_ft(zone, 10.0, 200.0), Err(AltitudeError::PressureTooHigh { .. })));
+        }
+    
+        #[test]
+        fn test_calculate_altitude_with_units() {
+            // Test that an altimeter reading in inHg gives the same altitude as the kPa reading
+            let zone = AtmosphereZone::Troposphere;
+            let temperature_c = 10.0; // Example temperature in Celsius
+    
+            let altitude_inhg = calculate_altitude_with_units(zone, temperature_c, TemperatureUnit::Celsius, 26.58, PressureUnit::InHg).unwrap();
+            let altitude_kpa = calculate_altitude(zone, temperature_c, to_kpa(26.58, PressureUnit::InHg)).unwrap();
+    
+            assert_eq!(altitude_inhg, altitude_kpa);
+            assert!((altitude_inhg - 1000.0).abs() < 50.0);
+    
+            // A Fahrenheit thermocouple reading is accepted directly
+            let altitude_f = calculate_altitude_with_units(zone, 50.0, TemperatureUnit::Fahrenheit, 26.58, PressureUnit::InHg).unwrap();
+    
+            assert_eq!(altitude_f, altitude_kpa);
+        }
+    
+        #[cfg(feature = "serde")]
+        #[test]
+        fn test_zone_serde_round_trip() {
+            // Test that each zone serializes to its variant name and back
+            for (zone, name) in [
+                (AtmosphereZone::Troposphere, "\"Troposphere\""),
+                (AtmosphereZone::LowerStratosphere, "\"LowerStratosphere\""),
+                (AtmosphereZone::UpperStratosphere, "\"UpperStratosphere\""),
+                (AtmosphereZone::Mesosphere, "\"Mesosphere\""),
+            ] {
+                let json = serde_json::to_string(&zone).unwrap();
+                assert_eq!(json, name);
+    
+                let parsed: AtmosphereZone = serde_json::from_str(&json).unwrap();
+                assert_eq!(parsed, zone);
+            }
+        }
+    
+        #[cfg(feature = "libm")]
+        #[test]
+        fn test_libm_backend() {
+            // Exercise each libm-backed operation so a no_std build links them
+            assert!((pressure_at_altitude(5000.0) - 54.0).abs() < 0.5); // powf in the troposphere
+            assert!((pressure_at_altitude(15000.0) - 12.1).abs() < 0.1); // exp in the lower stratosphere
+            assert!((calculate_altitude(AtmosphereZone::LowerStratosphere, -56.46, 12.1).unwrap() - 15000.0).abs() < 10.0); // ln
+            assert!((speed_of_sound(0.0) - 340.3).abs() < 0.5); // sqrt
+        }
+    
+        #[test]
+        fn test_determine_zone_const() {
+            // Test that zones can be classified at compile time
+            const ZONE: AtmosphereZone = determine_zone(5000.0);
+            const TABLE: [AtmosphereZone; 3] = [determine_zone(0.0), determine_zone(15000.0), determine_zone(30000.0)];
+    
+            assert_eq!(ZONE, AtmosphereZone::Troposphere);
+            assert_eq!(TABLE, [AtmosphereZone::Troposphere, AtmosphereZone::LowerStratosphere, AtmosphereZone::UpperStratosphere]);
+        }
+    
+        #[test]
+        fn test_properties_at_altitude() {
+            // Test that the bundled properties match the individual functions
+            for &altitude_m in &[0.0, 5000.0, 11000.0, 15000.0, 30000.0] {
+                let properties = properties_at_altitude(altitude_m);
+    
+                assert_eq!(properties.zone, determine_zone(altitude_m));
+                assert_eq!(properties.temperature_c, temperature_at_altitude(altitude_m));
+                assert_eq!(properties.pressure_kpa, pressure_at_altitude(altitude_m));
+                assert_eq!(properties.density_kgm3, density_at_altitude(altitude_m));
+            }
+        }
+    
+        #[test]
+        fn test_altitude_profile() {
+            // Test a 0–30000 m sweep in 1000 m steps
+            let mut count = 0;
+            let mut previous_pressure_kpa = f64::INFINITY;
+    
+            for properties in altitude_profile(0.0, 30000.0, 1000.0) {
+                // Pressure decreases monotonically with altitude
+                assert!(properties.pressure_kpa < previous_pressure_kpa);
+                previous_pressure_kpa = properties.pressure_kpa;
+                count += 1;
+            }
+    
+            assert_eq!(count, 31);
+        }
+    
+        #[test]
+        fn test_altitude_profile_edge_cases() {
+            // A descending sweep starts at the top
+            let mut descending = altitude_profile(30000.0, 0.0, 1000.0);
+            assert_eq!(descending.next().unwrap().pressure_kpa, pressure_at_altitude(30000.0));
+            assert_eq!(descending.count(), 30);
+    
+            // A zero step yields nothing rather than looping forever
+            assert_eq!(altitude_profile(0.0, 30000.0, 0.0).count(), 0);
+        }
+    
+        #[test]
+        fn test_below_sea_level() {
+            // Test an altitude near the Dead Sea shore
+            let altitude_m = -400.0;
+            let pressure_kpa = pressure_at_altitude(altitude_m);
+    
+            assert_eq!(determine_zone(altitude_m), AtmosphereZone::Troposphere);
+            assert!(pressure_kpa > 101.325); // Higher pressure below sea level
+    
+            let altitude = calculate_altitude(AtmosphereZone::Troposphere, 17.6, pressure_kpa);
+    
+            assert!((altitude.unwrap() - altitude_m).abs() < 1.0);
+        }
+    
+        #[test]
+        fn test_altitude_from_pressure() {
+            // Test that standard altitudes are recovered from their pressure alone
+            for &altitude_m in &[-400.0, 0.0, 1000.0, 5000.0, 11000.0, 15000.0, 25000.0, 30000.0, 40000.0] {
+                let altitude = altitude_from_pressure(pressure_at_altitude(altitude_m)).unwrap();
+    
+                assert!((altitude - altitude_m).abs() < 1.0);
+            }
+        }
+    
+        #[test]
+        fn test_altitude_from_pressure_continuity() {
+            // Small steps in pressure give small steps in altitude across every boundary
+            for &boundary_m in &[11000.0, 25000.0, 47000.0] {
+                let below = altitude_from_pressure(pressure_at_altitude(boundary_m - 0.5)).unwrap();
+                let above = altitude_from_pressure(pressure_at_altitude(boundary_m + 0.5)).unwrap();
+    
+                assert!((above - below - 1.0).abs() < 0.01);
+            }
+        }
+    
+        #[test]
+        fn test_altitude_from_pressure_invalid() {
+            // Test pressures outside the model and NaN
+            assert!(matches!(altitude_from_pressure(200.0), Err(AltitudeError::PressureTooHigh { .. })));
+            assert!(matches!(altitude_from_pressure(0.0001), Err(AltitudeError::PressureTooLow { .. })));
+            assert_eq!(altitude_from_pressure(f64::NAN), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_calculate_altitude_with_reference() {
+            // The default reference reproduces the standard calculation
+            let zone = AtmosphereZone::Troposphere;
+            let standard = calculate_altitude(zone, 10.0, 90.0).unwrap();
+            let referenced = calculate_altitude_with_reference(zone, 10.0, 90.0, SeaLevelReference::default()).unwrap();
+    
+            assert!((referenced - standard).abs() < 1e-9);
+    
+            // Raising the sea-level pressure leaves more pressure to lose, so the same reading is higher up
+            let high_qnh = SeaLevelReference { pressure_kpa: 103.0, temperature_c: 15.04 };
+            let raised = calculate_altitude_with_reference(zone, 10.0, 90.0, high_qnh).unwrap();
+    
+            assert!(raised > standard);
+    
+            // The reported altitude is always relative to the reference sea level
+            assert!(calculate_altitude_with_reference(zone, 10.0, 103.0, high_qnh).unwrap().abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_calculator_lapse_rate() {
+            // The default calculator reproduces the standard profile
+            let standard = AltitudeCalculator::default();
+    
+            assert!((standard.temperature_at_altitude(5000.0) - temperature_at_altitude(5000.0)).abs() < 1e-9);
+            assert!((standard.pressure_at_altitude(5000.0) - pressure_at_altitude(5000.0)).abs() < 1e-9);
+    
+            // A steeper lapse rate cools the air faster with height
+            let steep = AltitudeCalculator::new(SeaLevelReference::default(), 0.0098);
+    
+            assert!(steep.temperature_at_altitude(5000.0) < standard.temperature_at_altitude(5000.0));
+    
+            // Altitudes still round trip through the custom troposphere
+            let pressure = steep.pressure_at_altitude(5000.0);
+            let altitude = steep.calculate_altitude(AtmosphereZone::Troposphere, -34.0, pressure).unwrap();
+    
+            assert!((altitude - 5000.0).abs() < 1e-6);
+        }
+    
+        #[test]
+        fn test_zone_display() {
+            // Test the full name and abbreviation of every zone
+            extern crate std;
+            use std::string::ToString;
+    
+            for (zone, name, abbreviation) in [
+                (AtmosphereZone::Troposphere, "Troposphere", "TROP"),
+                (AtmosphereZone::LowerStratosphere, "Lower Stratosphere", "LSTR"),
+                (AtmosphereZone::UpperStratosphere, "Upper Stratosphere", "USTR"),
+                (AtmosphereZone::Mesosphere, "Mesosphere", "MESO"),
+            ] {
+                assert_eq!(zone.to_string(), name);
+                assert_eq!(zone.abbreviation(), abbreviation);
+            }
+        }
+    
+        #[test]
+        fn test_zone_from_str() {
+            // Test parsing zone names in any case and round tripping through Display
+            extern crate std;
+            use std::string::ToString;
+    
+            assert_eq!("troposphere".parse(), Ok(AtmosphereZone::Troposphere));
+            assert_eq!("LowerStratosphere".parse(), Ok(AtmosphereZone::LowerStratosphere));
+            assert_eq!("lower_stratosphere".parse(), Ok(AtmosphereZone::LowerStratosphere));
+            assert_eq!("UPPERSTRATOSPHERE".parse(), Ok(AtmosphereZone::UpperStratosphere));
+    
+            let zones = [
+                AtmosphereZone::Troposphere,
+                AtmosphereZone::LowerStratosphere,
+                AtmosphereZone::UpperStratosphere,
+                AtmosphereZone::Mesosphere,
+            ];
+            for zone in zones {
+                assert_eq!(zone.to_string().parse(), Ok(zone));
+            }
+    
+            // Misspelled and unknown names are rejected
+            assert_eq!("tropospere".parse::<AtmosphereZone>(), Err(ParseZoneError));
+            assert_eq!("lower-stratosphere".parse::<AtmosphereZone>(), Err(ParseZoneError));
+            assert_eq!("".parse::<AtmosphereZone>(), Err(ParseZoneError));
+        }
+    
+        #[test]
+        fn test_dynamic_viscosity() {
+            // Test the sea level viscosity of air
+            assert!((dynamic_viscosity(0.0) - 1.789e-5).abs() < 1.789e-7);
+    
+            // Colder air higher in the troposphere is less viscous
+            assert!(dynamic_viscosity(5000.0) < dynamic_viscosity(0.0));
+            assert!(dynamic_viscosity(10000.0) < dynamic_viscosity(5000.0));
+        }
+    
+        #[test]
+        fn test_reynolds_per_meter() {
+            // Test Re/m of a 20 m/s glider at sea level
+            let sea_level = reynolds_per_meter(20.0, 0.0);
+    
+            assert!(sea_level > 1.3e6 && sea_level < 1.5e6);
+            assert!((kinematic_viscosity(0.0) - 20.0 / sea_level).abs() < 1e-12);
+    
+            // Thinner air raises the kinematic viscosity, so Re/m falls with altitude
+            assert!(kinematic_viscosity(5000.0) > kinematic_viscosity(0.0));
+            assert!(reynolds_per_meter(20.0, 5000.0) < sea_level);
+            assert!(reynolds_per_meter(20.0, 20000.0) < reynolds_per_meter(20.0, 5000.0));
+        }
+    
+        #[test]
+        fn test_dynamic_pressure() {
+            // Test q at 50 m/s at sea level
+            assert!((dynamic_pressure(50.0, 0.0) - 1531.0).abs() < 2.0);
+    
+            // The same true airspeed produces less q in thinner air
+            assert!(dynamic_pressure(50.0, 5000.0) < dynamic_pressure(50.0, 0.0));
+        }
+    
+        #[test]
+        fn test_hypsometric_altitude() {
+            // Test the 1000-500 hPa thickness of a layer with a mean temperature of 0 °C
+            assert!((hypsometric_altitude(100.0, 50.0, 0.0) - 5542.0).abs() < 1.0);
+    
+            // With the standard mean temperature it agrees with the model profile
+            let mean_temp = (temperature_at_altitude(0.0) + temperature_at_altitude(5000.0)) / 2.0;
+            let altitude = hypsometric_altitude(pressure_at_altitude(0.0), pressure_at_altitude(5000.0), mean_temp);
+    
+            assert!((altitude - 5000.0).abs() < 5.0);
+        }
+    
+        #[test]
+        fn test_atmosphere_ratios() {
+            // Test that all ratios are 1 at sea level
+            assert!((pressure_ratio(0.0) - 1.0).abs() < 1e-12);
+            assert!((temperature_ratio(0.0) - 1.0).abs() < 1e-12);
+            assert!((density_ratio(0.0) - 1.0).abs() < 1e-12);
+    
+            // The ideal gas law ties the three together
+            let (delta, theta, sigma) = (pressure_ratio(8000.0), temperature_ratio(8000.0), density_ratio(8000.0));
+    
+            assert!((sigma - delta / theta).abs() < 1e-12);
+            assert!(delta < 1.0 && theta < 1.0 && sigma < 1.0);
+        }
+    
+        #[test]
+        fn test_tas_from_eas() {
+            // Test that the airspeeds agree at sea level
+            assert!((tas_from_eas(100.0, 0.0) - 100.0).abs() < 1e-9);
+    
+            // At 10000 m the air is about a third as dense, so TAS is roughly 1.7 times EAS
+            let tas = tas_from_eas(100.0, 10000.0);
+    
+            assert!((tas - 172.0).abs() < 2.0);
+            assert!((eas_from_tas(tas, 10000.0) - 100.0).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_calculator_builder() {
+            // The default builder reproduces the default calculator
+            assert_eq!(AltitudeCalculator::<f64>::builder().build(), Ok(AltitudeCalculator::default()));
+    
+            // Changing one setting changes the result
+            let zone = AtmosphereZone::Troposphere;
+            let standard = calculate_altitude(zone, 10.0, 90.0).unwrap();
+            let calculator = AltitudeCalculator::builder().sea_level_pressure(103.0).build().unwrap();
+    
+            assert!(calculator.calculate_altitude(zone, 10.0, 90.0).unwrap() > standard);
+    
+            // Combined settings, returning feet
+            let calculator = AltitudeCalculator::builder()
+                .sea_level_temperature(15.04)
+                .lapse_rate(0.00649)
+                .output_unit(AltitudeUnit::Feet)
+                .build()
+                .unwrap();
+            let altitude_ft = calculator.calculate_altitude(zone, 10.0, 90.0).unwrap();
+    
+            assert!((altitude_ft - standard * FEET_PER_METER).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_vertical_speed() {
+            // Test a climb of 5 m in one second
+            let p1 = pressure_at_altitude(1000.0);
+            let p2 = pressure_at_altitude(1005.0);
+    
+            assert!((vertical_speed(p1, 10.0, p2, 11.0).unwrap() - 5.0).abs() < 1e-6);
+            assert!((vertical_speed(p2, 10.0, p1, 12.0).unwrap() + 2.5).abs() < 1e-6);
+    
+            // Samples at the same time have no rate
+            assert_eq!(vertical_speed(p1, 10.0, p2, 10.0), Err(AltitudeError::InvalidInterval));
+        }
+    
+        #[test]
+        fn test_mesosphere() {
+            // A balloon at 40000 m is still in the upper stratosphere, the mesosphere starts above 47000 m
+            assert_eq!(determine_zone(40000.0), AtmosphereZone::UpperStratosphere);
+            assert_eq!(determine_zone(50000.0), AtmosphereZone::Mesosphere);
+    
+            // Test against the US Standard Atmosphere 1976 tables
+            let model = AtmosphereModel::UsStandard1976;
+    
+            assert!((temperature_at_altitude(50000.0) + 2.5).abs() < 0.01);
+    
+            // The temperature steps down where the two models join
+            let step = temperature_at_altitude(46999.999) - temperature_at_altitude(47000.001);
+            assert!((step - 11.82).abs() < 0.01);
+            assert!((pressure_at_altitude_with_model(50000.0, model) - 0.07594).abs() < 0.00001);
+            assert!((pressure_at_altitude_with_model(71000.0, model) - 0.003956).abs() < 0.00001);
+    
+            // The NASA model keeps the 1976 pressure profile, joined onto its upper stratosphere
+            let ratio = pressure_at_altitude(50000.0) / pressure_at_altitude(47000.0);
+            let table_ratio = pressure_at_altitude_with_model(50000.0, model) / pressure_at_altitude_with_model(47000.0, model);
+    
+            assert!((ratio - table_ratio).abs() < 1e-9);
+    
+            // Altitudes round trip through every mesosphere layer
+            for altitude in [48000.0, 60000.0, 80000.0] {
+                let pressure = pressure_at_altitude(altitude);
+                let calculated = calculate_altitude(AtmosphereZone::Mesosphere, 0.0, pressure).unwrap();
+    
+                assert!((calculated - altitude).abs() < 1e-6);
+                assert!((altitude_from_pressure(pressure).unwrap() - altitude).abs() < 1e-6);
+            }
+    
+            // The upper stratosphere no longer extrapolates into the mesosphere
+            let pressure = pressure_at_altitude(60000.0);
+            assert!(matches!(calculate_altitude(AtmosphereZone::UpperStratosphere, 0.0, pressure), Err(AltitudeError::PressureTooLow { .. })));
+        }
+    
+        #[test]
+        fn test_us_standard_1976() {
+            // Test against the published table pressures at 0, 11, 20 and 32 km
+            let model = AtmosphereModel::UsStandard1976;
+    
+            for (altitude, pressure) in [(0.0, 101.325), (11000.0, 22.632), (20000.0, 5.4749), (32000.0, 0.86802)] {
+                assert!((pressure_at_altitude_with_model(altitude, model) - pressure).abs() < pressure * 1e-4);
+            }
+    
+            // Altitudes round trip, and the zone limits follow the 1976 profile
+            let pressure = pressure_at_altitude_with_model(15000.0, model);
+            let altitude = calculate_altitude_with_model(AtmosphereZone::LowerStratosphere, -56.5, pressure, model).unwrap();
+    
+            assert!((altitude - 15000.0).abs() < 1e-6);
+            assert!(matches!(
+                calculate_altitude_with_model(AtmosphereZone::Troposphere, -56.5, pressure, model),
+                Err(AltitudeError::PressureTooLow { .. })
+            ));
+    
+            // The NASA model stays the default
+            assert_eq!(AtmosphereModel::default(), AtmosphereModel::Nasa1960);
+            assert!((pressure_at_altitude_with_model(5000.0, AtmosphereModel::Nasa1960) - pressure_at_altitude(5000.0)).abs() < 1e-12);
+        }
+    
+        #[test]
+        fn test_exponential_approximation() {
+            // The approximation is within 5% of the model at 5000 m
+            let error = |altitude: f64| (pressure_exponential(altitude) / pressure_at_altitude(altitude) - 1.0).abs();
+    
+            assert!(error(5000.0) < 0.05);
+            assert!(error(10000.0) > error(5000.0));
+            assert!(error(20000.0) > error(10000.0));
+    
+            // Both directions invert each other
+            assert!((altitude_exponential(pressure_exponential(3000.0)) - 3000.0).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_mars_atmosphere() {
+            // Test the datum pressure and temperature of the NASA Mars model
+            let mars: AltitudeCalculator = AltitudeCalculator::builder().planet(Planet::Mars).build().unwrap();
+    
+            assert!((mars.pressure_at_altitude(0.0) - 0.699).abs() < 1e-9);
+            assert!((mars.temperature_at_altitude(0.0) + 31.0).abs() < 1e-9);
+    
+            // At 5000 m about two thirds of the datum pressure remains
+            assert!((mars.pressure_at_altitude(5000.0) - 0.4465).abs() < 0.001);
+            assert!((mars.temperature_at_altitude(10000.0) + 45.6).abs() < 1e-9);
+    
+            let properties = mars.properties_at_altitude(0.0);
+            assert!((properties.density_kgm3 - 0.0150).abs() < 0.0005);
+    
+            // Altitudes round trip in either Mars zone
+            for (zone, altitude) in [(AtmosphereZone::Troposphere, 5000.0), (AtmosphereZone::LowerStratosphere, 12000.0)] {
+                let pressure = mars.pressure_at_altitude(altitude);
+                let calculated = mars.calculate_altitude(zone, -40.0, pressure).unwrap();
+    
+                assert!((calculated - altitude).abs() < 1e-6);
+            }
+    
+            // Earth stays the default
+            assert_eq!(Planet::default(), Planet::Earth);
         }
-    }
-
-    pub fn calculate_altitude(zone: AtmosphereZone, temperature_c: f64, pressure_kpa: f64) -> Option<f64> {
-        match zone {
-            AtmosphereZone::Troposphere => {
-                let t = 15.04f64; // Sea level standard temperature in Celsius
-                let p = 101.29f64 * ((t + 273.1f64) / 288.08f64).powf(5.256f64); // Pressure at sea level in kPa
     
-                if pressure_kpa > p || pressure_kpa <= 22.65f64 {
-                    return None; // Pressure is out of range for Troposphere
-                }
+        #[test]
+        fn test_mean_free_path() {
+            // Test the sea level number density and mean free path
+            assert!((number_density(0.0) / 2.55e25 - 1.0).abs() < 0.01);
+            assert!((mean_free_path(0.0) - 68e-9).abs() < 2e-9);
+    
+            // Molecules travel more than ten times further in the upper stratosphere
+            assert!(mean_free_path(30000.0) > 10.0 * mean_free_path(0.0));
+            assert!(mean_free_path(45000.0) > 100.0 * mean_free_path(0.0));
+        }
+    
+        #[test]
+        fn test_scale_height() {
+            // Test the sea level scale height
+            assert!((scale_height(0.0) - 8435.0).abs() < 5.0);
+    
+            // The cold tropopause has a smaller scale height
+            assert!(scale_height(11000.0) < scale_height(0.0));
+        }
+    
+        #[test]
+        fn test_hypsometric_altitude_with_gravity() {
+            // Standard gravity reproduces the plain hypsometric equation
+            let standard = hypsometric_altitude(100.0, 50.0, 0.0);
+    
+            assert!((hypsometric_altitude_with_gravity(100.0, 50.0, 0.0, STANDARD_GRAVITY) - standard).abs() < 1e-9);
+    
+            // Weaker gravity at the equator spreads the same pressure drop over a thicker layer
+            let equator = hypsometric_altitude_with_gravity(100.0, 50.0, 0.0, gravity(0.0, 2500.0));
+            let pole = hypsometric_altitude_with_gravity(100.0, 50.0, 0.0, gravity(90.0, 2500.0));
+    
+            assert!(equator > standard && standard > pole);
+            assert!(equator - pole < 0.006 * standard);
+        }
     
-                // Calculate altitude
-                let altitude = ((288.08f64 / (temperature_c + 273.1f64)).powf(1.0f64 / 5.256f64) - 1.0f64) * 288.08f64 / 0.00649f64;
-                Some(altitude)
+        #[test]
+        fn test_oxygen_partial_pressure() {
+            // Test the sea level oxygen partial pressure
+            assert!((oxygen_partial_pressure(0.0) - 21.2).abs() < 0.1);
+            assert!(!is_hypoxia_risk(0.0));
+            assert!(!is_hypoxia_risk(2500.0));
+    
+            // At 8000 m it is far below the threshold
+            assert!(oxygen_partial_pressure(8000.0) < 8.0);
+            assert!(is_hypoxia_risk(8000.0));
+        }
+    
+        #[test]
+        fn test_zone_boundary_continuity() {
+            // Both zones at a boundary report the same altitude for the boundary pressure
+            let boundaries = [
+                (11000.0, AtmosphereZone::Troposphere, AtmosphereZone::LowerStratosphere),
+                (25000.0, AtmosphereZone::LowerStratosphere, AtmosphereZone::UpperStratosphere),
+                (47000.0, AtmosphereZone::UpperStratosphere, AtmosphereZone::Mesosphere),
+            ];
+    
+            for (boundary_m, below, above) in boundaries {
+                let pressure = pressure_at_altitude(boundary_m);
+    
+                // Nudge the pressure just inside each zone so rounding does not trip its limit
+                let from_below = calculate_altitude(below, 0.0, pressure * (1.0 + 1e-12)).unwrap();
+                let from_above = calculate_altitude(above, 0.0, pressure * (1.0 - 1e-12)).unwrap();
+    
+                assert!((from_below - boundary_m).abs() < 1.0);
+                assert!((from_above - boundary_m).abs() < 1.0);
+    
+                // The pressure profile itself has no step
+                let step = pressure_at_altitude(boundary_m + 1e-6) - pressure_at_altitude(boundary_m - 1e-6);
+                assert!(step.abs() < pressure * 1e-6);
             }
-            AtmosphereZone::LowerStratosphere => {
-                let t = -56.56f64; // Constant temperature in Celsius
-                let p = 22.65f64 * (-0.000157f64 * 11_000.0f64).exp(); // Pressure at 11 000 m in kPa
+        }
     
-                if pressure_kpa > p || pressure_kpa <= 2.488f64 {
-                    return None; // Pressure is out of range for lower Stratosphere
-                }
+        #[test]
+        fn test_sea_level_pressure() {
+            // The standard sea level matches the canonical 101.325 kPa
+            assert!((pressure_at_altitude(0.0) - 101.325).abs() < 1e-9);
+            assert!((calculate_altitude(AtmosphereZone::Troposphere, 15.04, 101.325).unwrap()).abs() < 1e-6);
+    
+            // Temperatures convert with the exact Kelvin offset
+            assert!((temperature_ratio(0.0) - 1.0).abs() < 1e-12);
+            assert!((to_celsius(288.19, TemperatureUnit::Kelvin) - 15.04).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_model_constants() {
+            // The troposphere formula at 0 m returns the sea-level anchor
+            let reference = SeaLevelReference {
+                pressure_kpa: AltitudeCalculator::SEA_LEVEL_PRESSURE_KPA,
+                temperature_c: AltitudeCalculator::SEA_LEVEL_TEMPERATURE_C,
+            };
+            let calculator = AltitudeCalculator::new(reference, AltitudeCalculator::LAPSE_RATE);
     
-                let altitude = 11_000.0f64 + (pressure_kpa / 22.65f64).ln() / -0.000157f64;
-                Some(altitude)
+            assert!((calculator.pressure_at_altitude(0.0) - AltitudeCalculator::SEA_LEVEL_PRESSURE_KPA).abs() < 1e-12);
+            assert!((calculator.temperature_at_altitude(0.0) - AltitudeCalculator::SEA_LEVEL_TEMPERATURE_C).abs() < 1e-12);
+    
+            // The stratosphere anchors are the pressures the zone below reaches at its top
+            assert!((pressure_at_altitude(11000.0) - AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA).abs() < 1e-9);
+            assert!((pressure_at_altitude(25000.0) - AltitudeCalculator::STRATOPAUSE_PRESSURE_KPA).abs() < 1e-9);
+            assert!((temperature_at_altitude(25000.0) - AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_zone_from_pressure() {
+            // Classify pressures from each zone
+            assert_eq!(zone_from_pressure(90.0), AtmosphereZone::Troposphere);
+            assert_eq!(zone_from_pressure(15.0), AtmosphereZone::LowerStratosphere);
+            assert_eq!(zone_from_pressure(1.0), AtmosphereZone::UpperStratosphere);
+            assert_eq!(zone_from_pressure(0.01), AtmosphereZone::Mesosphere);
+    
+            // Agree with the zone of the altitude either side of each boundary
+            for &altitude_m in &[10999.0, 11001.0, 24999.0, 25001.0, 46999.0, 47001.0] {
+                assert_eq!(zone_from_pressure(pressure_at_altitude(altitude_m)), determine_zone(altitude_m));
             }
-            AtmosphereZone::UpperStratosphere => {
-                let t = -131.21f64 + 0.00299f64 * (25_000.0f64 - 25_000.0f64); // Constant temperature in Celsius
-                let p = 2.488f64 * ((t + 273.1f64) / 216.6f64).powf(-11.388f64); // Pressure at 25 000 m in kPa
+        }
     
-                if pressure_kpa > p {
-                    return None; // Pressure is out of range for Upper Stratosphere
-                }
+        #[test]
+        fn test_thermal_conductivity() {
+            // Test against the US Standard Atmosphere 1976 sea-level value
+            assert!((thermal_conductivity(0.0) - 0.025326).abs() < 1e-4);
     
-                let altitude = 25_000.0f64 + (pressure_kpa / 2.488f64).powf(-1.0f64 / 11.388f64) * (216.6f64 / 273.15f64);
-                Some(altitude)
+            // Colder air conducts less heat
+            assert!(thermal_conductivity(11000.0) < thermal_conductivity(0.0));
+        }
+    
+        #[test]
+        fn test_prandtl_number() {
+            // Air stays near 0.71 through the troposphere
+            let sea_level = prandtl_number(0.0);
+            assert!((sea_level - 0.72).abs() < 0.02);
+    
+            for &altitude_m in &[2000.0, 5000.0, 8000.0, 11000.0] {
+                assert!((prandtl_number(altitude_m) - sea_level).abs() < 0.03);
             }
         }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
     
         #[test]
-        fn test_determine_zone() {
-            // Test determining the atmospheric zone based on altitude
-            assert_eq!(determine_zone(5000.0), AtmosphereZone::Troposphere); // Altitude within Troposphere
-            assert_eq!(determine_zone(15000.0), AtmosphereZone::LowerStratosphere); // Altitude within Lower Stratosphere
-            assert_eq!(determine_zone(30000.0), AtmosphereZone::UpperStratosphere); // Altitude within Upper Stratosphere
+        fn test_altitude_from_pt() {
+            // Standard conditions give back the model altitude
+            let pressure = pressure_at_altitude(5000.0);
+            let standard = altitude_from_pt(pressure, temperature_at_altitude(5000.0)).unwrap();
+    
+            assert!((standard - 5000.0).abs() < 10.0);
+    
+            // Air 10 K warmer than standard puts the same pressure about 4 % higher
+            let warm = altitude_from_pt(pressure, temperature_at_altitude(5000.0) + 10.0).unwrap();
+    
+            assert!((warm - standard - 190.0).abs() < 20.0);
+    
+            // Readings outside the troposphere are rejected
+            assert!(altitude_from_pt(10.0, -56.0).is_err());
+            assert_eq!(altitude_from_pt(pressure, f64::NAN), Err(AltitudeError::InvalidInput));
         }
     
         #[test]
-        fn test_calculate_altitude_troposphere() {
-            // Test altitude calculation within the Troposphere
-            let zone = AtmosphereZone::Troposphere;
-            let temperature_c = 10.0; // Example temperature in Celsius
-            let pressure_kpa = 90.0; // Example pressure in kPa
+        fn test_altitudes_from_pressures() {
+            // Convert a short log of readings in one call
+            let pressures = [101.325, 79.5, 54.0];
+            let mut altitudes = [0.0; 3];
     
-            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+            assert_eq!(altitudes_from_pressures(&pressures, &mut altitudes), Ok(()));
+            for (altitude, pressure) in altitudes.iter().zip(pressures.iter()) {
+                assert!((altitude - altitude_from_pressure(*pressure).unwrap()).abs() < 1e-9);
+            }
     
-            // Check if the function returns some altitude value
-            assert!(altitude.is_some());
+            // Report the first reading out of range, keeping the ones before it
+            let pressures = [101.325, 79.5, 150.0, f64::NAN];
+            let mut altitudes = [f64::NAN; 4];
     
-            // Verify the calculated altitude is close to an expected range (within ±500 m of 2000 m)
-            assert!((altitude.unwrap() - 2000.0).abs() < 500.0);
+            assert_eq!(altitudes_from_pressures(&pressures, &mut altitudes), Err(AltitudeError::InvalidSample { index: 2 }));
+            assert!(altitudes[1].is_finite());
+    
+            // Both slices must have the same length
+            assert_eq!(altitudes_from_pressures(&pressures, &mut [0.0; 2]), Err(AltitudeError::LengthMismatch));
         }
     
         #[test]
-        fn test_calculate_altitude_lower_stratosphere() {
-            // Test altitude calculation within the Lower Stratosphere
-            let zone = AtmosphereZone::LowerStratosphere;
-            let temperature_c = -56.5; // Constant temperature in Celsius for this zone
-            let pressure_kpa = 20.0; // Example pressure in kPa
+        fn test_round_trip_error() {
+            // Every meter up to 30 000 m survives the trip through pressure
+            let mut worst: f64 = 0.0;
+            for step in 0..=30000 {
+                worst = worst.max(round_trip_error(step as f64));
+            }
     
-            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+            assert!(worst < 1e-9);
     
-            // Check if the function returns some altitude value
-            assert!(altitude.is_some());
+            // Outside the model range there is nothing to compare
+            assert!(round_trip_error(-1000.0).is_nan());
+        }
     
-            // Verify the calculated altitude is close to an expected range (within ±500 m of 12000 m)
-            assert!((altitude.unwrap() - 12000.0).abs() < 500.0);
+        #[test]
+        fn test_impact_pressure() {
+            // Mach 0.5 at sea level compresses the air by 18.6 % of the static pressure
+            let static_pressure = pressure_at_altitude(0.0);
+            assert!((impact_pressure(0.5, 0.0) / static_pressure - 0.18621).abs() < 1e-5);
+            assert!(impact_pressure(0.0, 0.0).abs() < 1e-12);
+    
+            // Both formulas meet at Mach 1
+            let subsonic = impact_pressure(1.0, 10000.0);
+            let supersonic = impact_pressure(1.0 + 1e-9, 10000.0);
+            assert!((subsonic - supersonic).abs() < 1e-6);
+            assert!((subsonic / pressure_at_altitude(10000.0) - 0.89293).abs() < 1e-5);
+    
+            // Mach 2 behind a normal shock, from the compressible flow tables
+            assert!((impact_pressure(2.0, 10000.0) / pressure_at_altitude(10000.0) - 4.6399).abs() < 1e-3);
         }
     
         #[test]
-        fn test_calculate_altitude_upper_stratosphere() {
-            // Test altitude calculation within the Upper Stratosphere
+        fn test_isa_calculator() {
+            // The ISA preset reproduces the standard tables at 5000 m
+            let isa: AltitudeCalculator = AltitudeCalculator::isa();
+    
+            assert!((isa.pressure_at_altitude(5000.0) - 54.0199).abs() < 1e-3);
+            assert!((isa.temperature_at_altitude(5000.0) + 17.5).abs() < 1e-9);
+            assert!((isa.pressure_at_altitude(0.0) - 101.325).abs() < 1e-9);
+    
+            // The NASA defaults are slightly warmer
+            let nasa: AltitudeCalculator = AltitudeCalculator::default();
+            assert!((nasa.temperature_at_altitude(5000.0) - isa.temperature_at_altitude(5000.0) - 0.09).abs() < 1e-9);
+        }
+    
+        #[test]
+        fn test_time_of_useful_consciousness() {
+            // FL250 gives 3 to 6 minutes and FL430 9 to 12 seconds
+            let fl250 = time_of_useful_consciousness(7600.0).unwrap();
+            assert!((180.0..=360.0).contains(&fl250));
+    
+            let fl430 = time_of_useful_consciousness(13000.0).unwrap();
+            assert!((9.0..=12.0).contains(&fl430));
+    
+            // Lower cabins are not limited and the highest keep the last band
+            assert_eq!(time_of_useful_consciousness(3000.0), None);
+            assert_eq!(time_of_useful_consciousness(20000.0), Some(7.5));
+        }
+    
+        #[test]
+        fn test_output_unit_kilometers() {
+            // Report a stratosphere altitude in kilometers and nautical miles
             let zone = AtmosphereZone::UpperStratosphere;
-            let temperature_c = -55.0; // Example temperature in Celsius
-            let pressure_kpa = 1.0; // Example pressure in kPa
+            let pressure = pressure_at_altitude(30000.0);
     
-            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+            let km: AltitudeCalculator = AltitudeCalculator::builder().output_unit(AltitudeUnit::Kilometers).build().unwrap();
+            assert!((km.calculate_altitude(zone, -41.5, pressure).unwrap() - 30.0).abs() < 1e-6);
     
-            // Check if the function returns some altitude value
-            assert!(altitude.is_some());
+            let nmi: AltitudeCalculator = AltitudeCalculator::builder().output_unit(AltitudeUnit::NauticalMiles).build().unwrap();
+            assert!((nmi.calculate_altitude(zone, -41.5, pressure).unwrap() - 16.1987).abs() < 1e-4);
+        }
     
-            // Verify the calculated altitude is close to an expected range (within ±2000 m of 26000 m)
-            assert!((altitude.unwrap() - 26000.0).abs() < 2000.0);
+        #[test]
+        fn test_bounds_policy_clamp() {
+            // Out-of-range pressures saturate at the zone boundaries
+            let clamping: AltitudeCalculator = AltitudeCalculator::builder().bounds_policy(BoundsPolicy::Clamp).build().unwrap();
+            let zone = AtmosphereZone::LowerStratosphere;
+    
+            assert_eq!(clamping.calculate_altitude(zone, -56.5, 50.0), Ok(11000.0));
+            assert_eq!(clamping.calculate_altitude(zone, -56.5, 1.0), Ok(25000.0));
+            assert_eq!(clamping.calculate_altitude(AtmosphereZone::Troposphere, 15.0, 120.0), Ok(-500.0));
+    
+            // Readings in range and invalid readings are unaffected
+            let pressure = pressure_at_altitude(20000.0);
+            assert!((clamping.calculate_altitude(zone, -56.5, pressure).unwrap() - 20000.0).abs() < 1e-6);
+            assert_eq!(clamping.calculate_altitude(zone, f64::NAN, pressure), Err(AltitudeError::InvalidInput));
+    
+            // The default keeps reporting errors
+            assert!(matches!(calculate_altitude(zone, -56.5, 50.0), Err(AltitudeError::PressureTooHigh { .. })));
         }
     
         #[test]
-        fn test_invalid_pressure() {
-            // Test invalid pressure for the Troposphere
+        fn test_zone_boundary_pressure() {
+            // Neighbouring zones share their boundary pressure
+            let zones = [
+                AtmosphereZone::Troposphere,
+                AtmosphereZone::LowerStratosphere,
+                AtmosphereZone::UpperStratosphere,
+                AtmosphereZone::Mesosphere,
+            ];
+            for pair in zones.windows(2) {
+                assert_eq!(zone_boundary_pressure(pair[0]).1, zone_boundary_pressure(pair[1]).0);
+            }
+    
+            // The limits are the ones the zone formulas check
+            let (upper, lower) = zone_boundary_pressure(AtmosphereZone::Troposphere);
+            assert!((lower - AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA).abs() < 1e-9);
+            assert!(calculate_altitude(AtmosphereZone::Troposphere, 15.0, upper).is_ok());
+            assert!(calculate_altitude(AtmosphereZone::Troposphere, 15.0, upper * 1.0001).is_err());
+        }
+    
+        #[test]
+        fn test_altitude_and_zone_from_pressure() {
+            // A mid-stratosphere pressure returns its altitude and zone together
+            let (altitude, zone) = altitude_and_zone_from_pressure(pressure_at_altitude(18000.0)).unwrap();
+    
+            assert!((altitude - 18000.0).abs() < 1e-6);
+            assert_eq!(zone, AtmosphereZone::LowerStratosphere);
+            assert_eq!(zone, zone_from_pressure(pressure_at_altitude(18000.0)));
+    
+            // Errors pass through unchanged
+            assert_eq!(altitude_and_zone_from_pressure(f64::NAN), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_altitude_of_pressure_level() {
+            // Test the standard levels against their ICAO heights
+            assert!((altitude_of_pressure_level(500.0) - 5574.0).abs() < 20.0);
+            assert!((altitude_of_pressure_level(300.0) - 9164.0).abs() < 20.0);
+            assert!((altitude_of_pressure_level(850.0) - 1457.0).abs() < 20.0);
+    
+            // Levels outside the model have no altitude
+            assert!(altitude_of_pressure_level(2000.0).is_nan());
+        }
+    
+        #[test]
+        fn test_zone_ordering() {
+            // Sorting zones puts them in order of altitude
+            let mut zones = [
+                AtmosphereZone::UpperStratosphere,
+                AtmosphereZone::Mesosphere,
+                AtmosphereZone::Troposphere,
+                AtmosphereZone::LowerStratosphere,
+            ];
+            zones.sort_unstable();
+    
+            assert_eq!(
+                zones,
+                [
+                    AtmosphereZone::Troposphere,
+                    AtmosphereZone::LowerStratosphere,
+                    AtmosphereZone::UpperStratosphere,
+                    AtmosphereZone::Mesosphere,
+                ]
+            );
+    
+            // The order matches the zones of increasing altitudes
+            assert!(determine_zone(5000.0) < determine_zone(15000.0));
+            assert!(determine_zone(30000.0) < determine_zone(60000.0));
+        }
+    
+        #[test]
+        fn test_lapse_rate() {
+            // Test the gradient of each zone
+            assert_eq!(lapse_rate(AtmosphereZone::Troposphere), -0.00649);
+            assert_eq!(lapse_rate(AtmosphereZone::LowerStratosphere), 0.0);
+            assert_eq!(lapse_rate(AtmosphereZone::UpperStratosphere), 0.00299);
+            assert_eq!(lapse_rate(AtmosphereZone::Mesosphere), -0.0028);
+    
+            // The gradients reproduce the temperature profile
+            for &(zone, bottom_m, top_m) in &[
+                (AtmosphereZone::Troposphere, 0.0, 11000.0),
+                (AtmosphereZone::LowerStratosphere, 11001.0, 25000.0),
+                (AtmosphereZone::UpperStratosphere, 25001.0, 47000.0),
+                (AtmosphereZone::Mesosphere, 51000.0, 71000.0),
+            ] {
+                let change = temperature_at_altitude(top_m) - temperature_at_altitude(bottom_m);
+                assert!((change - lapse_rate(zone) * (top_m - bottom_m)).abs() < 1e-6);
+            }
+        }
+    
+        #[test]
+        fn test_altitude_uncertainty() {
+            // A 0.12 hPa barometer resolves about 1 m at sea level
+            let sea_level = altitude_uncertainty(101.325, 0.012);
+            assert!((sea_level - 1.0).abs() < 0.05);
+    
+            // The same error is worth far more in the thin stratosphere
+            let stratosphere = altitude_uncertainty(pressure_at_altitude(20000.0), 0.012);
+            assert!(stratosphere > 10.0 * sea_level);
+    
+            // The slope matches a numerical derivative in every zone
+            for &altitude_m in &[1000.0, 18000.0, 35000.0, 60000.0] {
+                let p = pressure_at_altitude(altitude_m);
+                let dp = p * 1e-6;
+                let numerical = (altitude_from_pressure(p - dp).unwrap() - altitude_from_pressure(p + dp).unwrap()) / 2.0;
+    
+                assert!((altitude_uncertainty(p, dp) / numerical - 1.0).abs() < 1e-4);
+            }
+        }
+    
+        #[test]
+        fn test_properties_display() {
+            extern crate std;
+            use std::string::ToString;
+    
+            // Test the log line for sea level and the stratosphere
+            assert_eq!(properties_at_altitude(0.0).to_string(), "TROP T=15.0C P=101.3kPa rho=1.225");
+            assert_eq!(properties_at_altitude(20000.0).to_string(), "LSTR T=-56.5C P=5.5kPa rho=0.089");
+        }
+    
+        #[test]
+        fn test_specific_weight() {
+            // Test the sea level specific weight
+            assert!((specific_weight(0.0) - 12.01).abs() < 0.01);
+    
+            // Test that it follows the density and gravity
+            for altitude_m in [0.0, 5000.0, 15000.0, 40000.0] {
+                let weight = density_at_altitude(altitude_m) * STANDARD_GRAVITY;
+                assert!((specific_weight(altitude_m) - weight).abs() < 1e-12);
+            }
+            let local = density_at_altitude(0.0) * gravity(90.0, 0.0);
+            assert!(local > specific_weight(0.0));
+        }
+    
+        #[test]
+        fn test_buoyant_lift() {
+            // Test helium lift at sea level
+            let helium = lifting_gas_density(HELIUM_DENSITY_STP, 0.0);
+            assert!((buoyant_lift(0.0, helium) - 10.3).abs() < 0.1);
+    
+            // Hydrogen lifts more than helium
+            assert!(buoyant_lift(0.0, lifting_gas_density(HYDROGEN_DENSITY_STP, 0.0)) > buoyant_lift(0.0, helium));
+    
+            // Lift falls with the air density
+            let mut last = f64::MAX;
+            for altitude_m in [0.0, 5000.0, 10000.0, 20000.0, 30000.0] {
+                let lift = buoyant_lift(altitude_m, lifting_gas_density(HELIUM_DENSITY_STP, altitude_m));
+                assert!(lift > 0.0 && lift < last);
+                last = lift;
+            }
+        }
+    
+        #[test]
+        fn test_calculate_altitude_isa_offset() {
+            // Test that ISA+0 reproduces the standard altitude
+            for pressure_kpa in [101.325, 70.0, 30.0, 10.0, 1.0] {
+                let standard = altitude_from_pressure(pressure_kpa).unwrap();
+                assert!((calculate_altitude_isa_offset(pressure_kpa, 0.0).unwrap() - standard).abs() < 1e-6);
+            }
+    
+            // A warm day reaches the same pressure higher up, a cold one lower
+            let standard = calculate_altitude_isa_offset(50.0, 0.0).unwrap();
+            let warm = calculate_altitude_isa_offset(50.0, 20.0).unwrap();
+            assert!(warm > standard);
+            assert!(calculate_altitude_isa_offset(50.0, -20.0).unwrap() < standard);
+    
+            // About 4 % per 10 degrees at this height
+            assert!((warm / standard - 1.08).abs() < 0.01);
+            assert_eq!(calculate_altitude_isa_offset(50.0, f64::NAN), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_layers() {
+            // Test the layer lookup
+            assert_eq!(layer_for(5000.0), 0);
+            assert_eq!(layer_for(-100.0), 0);
+            assert_eq!(layer_for(15000.0), 1);
+            assert_eq!(layer_for(80000.0), LAYERS.len() - 1);
+    
+            // Each layer ends at the temperature and pressure the next one starts from
+            for (i, pair) in LAYERS.windows(2).enumerate() {
+                let (base_m, base_k, base_pa, lapse) = pair[0];
+                let (top_m, top_k, top_pa, _) = pair[1];
+                assert!(top_m > base_m);
+                assert_eq!(layer_for(top_m), i + 1);
+                assert_eq!(layer_for(top_m - 1.0), i);
+                assert!((base_k + lapse * (top_m - base_m) - top_k).abs() < 1e-9);
+                assert!(top_pa < base_pa);
+    
+                let model = pressure_at_altitude_with_model(top_m, AtmosphereModel::UsStandard1976);
+                assert!((model * 1000.0 - top_pa).abs() / top_pa < 1e-4);
+            }
+        }
+    
+        #[test]
+        fn test_altitude_stream() {
+            // Test that a bad reading is reported in place without ending the stream
+            let readings = [101.325, 50.0, f64::NAN, 20.0];
+            let mut stream = altitude_stream(readings.iter().copied());
+    
+            assert!(stream.next().unwrap().unwrap().abs() < 1.0);
+            assert_eq!(stream.next().unwrap(), altitude_from_pressure(50.0));
+            assert_eq!(stream.next().unwrap(), Err(AltitudeError::InvalidInput));
+            assert_eq!(stream.next().unwrap(), altitude_from_pressure(20.0));
+            assert!(stream.next().is_none());
+    
+            // Nothing is converted before it is asked for
+            let mut count = 0;
+            let lazy = altitude_stream(readings.iter().inspect(|_| count += 1).copied());
+            assert_eq!(lazy.take(2).count(), 2);
+            assert_eq!(count, 2);
+        }
+    
+        #[test]
+        fn test_builder_rejects_impossible_settings() {
+            // Test a valid non-standard configuration
+            let calculator = AltitudeCalculator::builder().sea_level_pressure(98.0).sea_level_temperature(-40.0).build();
+            assert!(calculator.is_ok());
+    
+            // Test each rejected setting
+            let builder = AltitudeCalculator::<f64>::builder;
+            assert_eq!(builder().sea_level_pressure(0.0).build(), Err(ConfigError::NonPositivePressure));
+            assert_eq!(builder().sea_level_pressure(-101.325).build(), Err(ConfigError::NonPositivePressure));
+            assert_eq!(builder().sea_level_temperature(-300.0).build(), Err(ConfigError::BelowAbsoluteZero));
+            assert_eq!(builder().sea_level_pressure(f64::NAN).build(), Err(ConfigError::InvalidSetting));
+            assert_eq!(builder().sea_level_temperature(f64::NAN).build(), Err(ConfigError::InvalidSetting));
+            assert_eq!(builder().lapse_rate(f64::INFINITY).build(), Err(ConfigError::InvalidSetting));
+        }
+    
+        #[test]
+        fn test_altitude_of_temperature() {
+            // Test the freezing level and its round trip
+            let freezing = altitude_of_temperature(0.0).unwrap();
+            assert!((freezing - 2317.0).abs() < 1.0);
+            assert!(temperature_at_altitude(freezing).abs() < 1e-9);
+    
+            // Temperatures the troposphere never reaches
+            assert_eq!(altitude_of_temperature(-60.0), None);
+            assert_eq!(altitude_of_temperature(30.0), None);
+            assert_eq!(altitude_of_temperature(f64::NAN), None);
+        }
+    
+        #[test]
+        fn test_potential_temperature() {
+            // At the reference pressure the potential temperature is the temperature itself
+            assert!((potential_temperature(15.0, 100.0) - 288.15).abs() < 1e-9);
+            assert!((potential_temperature(-30.0, 100.0) - 243.15).abs() < 1e-9);
+    
+            // Air at 50 kPa and -20 C is about 308 K when brought down
+            assert!((potential_temperature(-20.0, 50.0) - 308.53).abs() < 0.05);
+        }
+    
+        #[test]
+        fn test_layer_mean_temperature() {
+            // An isothermal layer has the temperature of the layer
+            let isothermal = layer_mean_temperature(12000.0, 20000.0);
+            assert!((isothermal - AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C).abs() < 1e-9);
+    
+            // A linear layer lies between its end temperatures, whichever way round it is given
+            let troposphere = layer_mean_temperature(0.0, 10000.0);
+            assert!(troposphere < temperature_at_altitude(0.0) && troposphere > temperature_at_altitude(10000.0));
+            assert!((layer_mean_temperature(10000.0, 0.0) - troposphere).abs() < 1e-9);
+    
+            // The mean of a span across several zones gives back its thickness, to within how far
+            // the NASA curve fits stray from hydrostatic balance
+            for (lower, upper) in [(0.0, 10000.0), (0.0, 30000.0), (5000.0, 60000.0)] {
+                let mean = layer_mean_temperature(lower, upper);
+                let thickness = hypsometric_altitude(pressure_at_altitude(lower), pressure_at_altitude(upper), mean);
+                assert!((thickness / (upper - lower) - 1.0).abs() < 0.005);
+            }
+        }
+    
+        #[test]
+        fn test_zone_all() {
+            // Adding a variant breaks this match, as a reminder to list it in all()
+            let index = |zone: AtmosphereZone| match zone {
+                AtmosphereZone::Troposphere => 0,
+                AtmosphereZone::LowerStratosphere => 1,
+                AtmosphereZone::UpperStratosphere => 2,
+                AtmosphereZone::Mesosphere => 3,
+            };
+    
+            // Every variant is listed once, in altitude order
+            assert_eq!(AtmosphereZone::all().len(), 4);
+            for (i, &zone) in AtmosphereZone::all().iter().enumerate() {
+                assert_eq!(index(zone), i);
+            }
+            assert!(AtmosphereZone::all().windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    
+        #[test]
+        fn test_pressure_at_altitude_hpa() {
+            // Test sea level and 5000 m in hPa
+            assert!((pressure_at_altitude_hpa(0.0) - 1013.25).abs() < 0.01);
+            assert!((pressure_at_altitude_hpa(5000.0) - 540.0).abs() < 1.0);
+            assert_eq!(pressure_at_altitude_hpa(5000.0), pressure_at_altitude(5000.0) * 10.0);
+        }
+    
+        #[test]
+        fn test_below_absolute_zero() {
+            // Test the shared conversion
+            assert_eq!(generic::celsius_to_kelvin_checked(15.0), Ok(288.15));
+            assert_eq!(generic::celsius_to_kelvin_checked(-KELVIN_OFFSET), Ok(0.0));
+            assert_eq!(generic::celsius_to_kelvin_checked(-300.0), Err(AltitudeError::BelowAbsoluteZero));
+            assert_eq!(generic::celsius_to_kelvin_checked(f64::NAN), Err(AltitudeError::InvalidInput));
+    
+            // Impossible temperatures are a clean error rather than a NaN altitude
+            let error = Err(AltitudeError::BelowAbsoluteZero);
+            assert_eq!(calculate_altitude(AtmosphereZone::Troposphere, -300.0, 90.0), error);
+            assert_eq!(altitude_from_pt(90.0, -300.0), error);
+            assert_eq!(calculate_altitude_isa_offset(50.0, -400.0), error);
+    
+            let reference = SeaLevelReference { pressure_kpa: 101.325, temperature_c: -300.0 };
+            assert_eq!(calculate_altitude_with_reference(AtmosphereZone::Troposphere, 10.0, 90.0, reference), error);
+        }
+    
+        #[test]
+        fn test_altitude_best_effort() {
+            let pressure_kpa = 80.0;
+    
+            // Pressure alone uses the standard inverse
+            assert_eq!(altitude_best_effort(pressure_kpa, None, None), altitude_from_pressure(pressure_kpa));
+    
+            // A temperature uses the hypsometric equation
+            assert_eq!(altitude_best_effort(pressure_kpa, Some(0.0), None), altitude_from_pt(pressure_kpa, 0.0));
+    
+            // A QNH alone gives the height above the QNH level, less for a lower setting
+            let standard = altitude_best_effort(pressure_kpa, None, Some(1013.25)).unwrap();
+            assert!((standard - altitude_from_pressure(pressure_kpa).unwrap()).abs() < 1.0);
+            let low = altitude_best_effort(pressure_kpa, None, Some(1003.25)).unwrap();
+            assert!((standard - low - 84.0).abs() < 5.0);
+    
+            // A temperature with a QNH measures the hypsometric column up from the QNH
+            let hypsometric = altitude_best_effort(pressure_kpa, Some(0.0), Some(1013.25)).unwrap();
+            assert!((hypsometric - altitude_from_pt(pressure_kpa, 0.0).unwrap()).abs() < 1e-9);
+            assert!(altitude_best_effort(pressure_kpa, Some(0.0), Some(1003.25)).unwrap() < hypsometric);
+    
+            // A bad reading is an error on every path
+            assert!(altitude_best_effort(f64::NAN, None, None).is_err());
+            assert!(altitude_best_effort(pressure_kpa, Some(f64::NAN), None).is_err());
+            assert!(altitude_best_effort(pressure_kpa, None, Some(f64::NAN)).is_err());
+            assert!(altitude_best_effort(pressure_kpa, Some(0.0), Some(f64::NAN)).is_err());
+        }
+    
+        #[test]
+        fn test_altitude_pressure_sensitivity() {
+            // Test the analytic slope against a central difference in every zone
+            for altitude_m in [0.0, 5000.0, 15000.0, 30000.0, 45000.0, 60000.0] {
+                let pressure_kpa = pressure_at_altitude(altitude_m);
+                let dp = pressure_kpa * 1e-5;
+                let above = altitude_from_pressure(pressure_kpa - dp).unwrap();
+                let below = altitude_from_pressure(pressure_kpa + dp).unwrap();
+                let finite_difference = (below - above) / (2.0 * dp);
+    
+                let slope = altitude_pressure_sensitivity(pressure_kpa);
+                assert!(slope < 0.0);
+                assert!((slope / finite_difference - 1.0).abs() < 1e-4);
+            }
+            assert!((altitude_pressure_sensitivity(101.325) + 84.0).abs() < 1.0);
+            assert!(altitude_pressure_sensitivity(f64::NAN).is_nan());
+        }
+    
+        #[test]
+        fn test_mars_pressure_limits() {
+            // Non-positive pressures are rejected rather than giving NaN or infinity
+            let mars: AltitudeCalculator = AltitudeCalculator::builder().planet(Planet::Mars).build().unwrap();
+            let lower = AtmosphereZone::Troposphere;
+            let upper = AtmosphereZone::LowerStratosphere;
+    
+            assert_eq!(mars.calculate_altitude(lower, -40.0, -1.0), Err(AltitudeError::InvalidInput));
+            assert_eq!(mars.calculate_altitude(lower, -40.0, 0.0), Err(AltitudeError::InvalidInput));
+    
+            // Earth pressures and readings from the other zone are out of range
+            assert!(matches!(mars.calculate_altitude(lower, -40.0, 100.0), Err(AltitudeError::PressureTooHigh { .. })));
+            let p_12000 = mars.pressure_at_altitude(12000.0);
+            assert!(matches!(mars.calculate_altitude(lower, -40.0, p_12000), Err(AltitudeError::PressureTooLow { .. })));
+            let p_5000 = mars.pressure_at_altitude(5000.0);
+            assert!(matches!(mars.calculate_altitude(upper, -40.0, p_5000), Err(AltitudeError::PressureTooHigh { .. })));
+    
+            // Clamping saturates at the Mars zone boundaries
+            let clamping: AltitudeCalculator =
+                AltitudeCalculator::builder().planet(Planet::Mars).bounds_policy(BoundsPolicy::Clamp).build().unwrap();
+            assert_eq!(clamping.calculate_altitude(lower, -40.0, p_12000), Ok(7000.0));
+            assert_eq!(clamping.calculate_altitude(upper, -40.0, p_5000), Ok(7000.0));
+            assert_eq!(clamping.calculate_altitude(lower, -40.0, 100.0), Ok(-8200.0));
+            assert_eq!(clamping.calculate_altitude(lower, -40.0, 0.0), Err(AltitudeError::InvalidInput));
+        }
+    
+        #[test]
+        fn test_mesosphere_pressure_limit() {
+            // Pressures below the model top are rejected like altitude_from_pressure does
+            let zone = AtmosphereZone::Mesosphere;
+            let limit = pressure_at_altitude(84852.0);
+            assert_eq!(altitude_from_pressure(1e-9), Err(AltitudeError::PressureTooLow { limit_kpa: limit }));
+    
+            for pressure_kpa in [1e-9, 0.0, -1.0] {
+                match calculate_altitude(zone, -80.0, pressure_kpa) {
+                    Err(AltitudeError::PressureTooLow { limit_kpa }) => assert!((limit_kpa / limit - 1.0).abs() < 1e-9),
+                    result => panic!("unexpected {:?}", result),
+                }
+            }
+            assert_eq!(calculate_altitude(zone, -80.0, f64::NAN), Err(AltitudeError::InvalidInput));
+    
+            // The model top itself is still inside the zone, just above it is not
+            let top = calculate_altitude(zone, -80.0, limit * (1.0 + 1e-9)).unwrap();
+            assert!((top - 84852.0).abs() < 1.0);
+    
+            // Clamping saturates at the model top
+            let clamping: AltitudeCalculator =
+                AltitudeCalculator::builder().bounds_policy(BoundsPolicy::Clamp).build().unwrap();
+            assert_eq!(clamping.calculate_altitude(zone, -80.0, 0.0), Ok(84852.0));
+        }
+    
+        #[test]
+        fn test_builder_rejects_lapse_rate() {
+            // Test lapse rates no troposphere has
+            let builder = AltitudeCalculator::<f64>::builder;
+            assert_eq!(builder().lapse_rate(0.0).build(), Err(ConfigError::InvalidLapseRate));
+            assert_eq!(builder().lapse_rate(-0.0065).build(), Err(ConfigError::InvalidLapseRate));
+            assert_eq!(builder().lapse_rate(0.05).build(), Err(ConfigError::InvalidLapseRate));
+    
+            // Steep but real lapse rates are accepted
+            assert!(builder().lapse_rate(0.0098).build().is_ok());
+            assert!(builder().lapse_rate(0.004).build().is_ok());
+        }
+    
+        #[test]
+        fn test_calculate_altitude_with_invalid_reference() {
+            // Test references that are not finite or not positive
             let zone = AtmosphereZone::Troposphere;
-            let temperature_c = 10.0; // Example temperature in Celsius
-            let pressure_kpa = 200.0; // Pressure too high for the Troposphere
+            for pressure_kpa in [f64::NAN, f64::INFINITY, 0.0, -101.325] {
+                let reference = SeaLevelReference { pressure_kpa, temperature_c: 15.0 };
+                assert_eq!(calculate_altitude_with_reference(zone, 10.0, 90.0, reference), Err(AltitudeError::InvalidInput));
+            }
+            let reference = SeaLevelReference { pressure_kpa: 101.325, temperature_c: f64::NAN };
+            assert_eq!(calculate_altitude_with_reference(zone, 10.0, 90.0, reference), Err(AltitudeError::InvalidInput));
     
-            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+            // A valid reference still works
+            let reference = SeaLevelReference { pressure_kpa: 101.325, temperature_c: 15.04 };
+            assert_eq!(calculate_altitude_with_reference(zone, 10.0, 90.0, reference), calculate_altitude(zone, 10.0, 90.0));
+        }
+    
+        #[test]
+        fn test_altitude_best_effort_above_troposphere() {
+            // A temperature above the troposphere does not stop the pressure-only methods
+            let pressure_only = altitude_best_effort(10.0, None, None).unwrap();
+            assert_eq!(altitude_best_effort(10.0, Some(-56.0), None), Ok(pressure_only));
+            assert!((pressure_only - 16220.0).abs() < 10.0);
+    
+            let with_qnh = altitude_best_effort(10.0, None, Some(1003.25));
+            assert_eq!(altitude_best_effort(10.0, Some(-56.0), Some(1003.25)), with_qnh);
     
-            // Ensure the function returns None for invalid input
-            assert!(altitude.is_none());
+            // A faulty temperature is still reported
+            assert_eq!(altitude_best_effort(10.0, Some(f64::NAN), None), Err(AltitudeError::InvalidInput));
+    
+            // Above standard sea-level pressure the height is measured up from a higher QNH
+            assert!(altitude_best_effort(102.0, Some(15.0), Some(1030.0)).unwrap() > 0.0);
+    
+            // Below the troposphere the temperature is left out the same way
+            let pressure_only = altitude_best_effort(110.0, None, Some(1030.0));
+            assert!(matches!(pressure_only, Err(AltitudeError::PressureTooHigh { .. })));
+            assert_eq!(altitude_best_effort(110.0, Some(15.0), Some(1030.0)), pressure_only);
+            assert_eq!(altitude_best_effort(110.0, Some(f64::NAN), None), Err(AltitudeError::InvalidInput));
         }
     
         #[test]
-        fn test_invalid_zone_pressure() {
-            // Test invalid pressure for the Lower Stratosphere
-            let zone = AtmosphereZone::LowerStratosphere;
-            let temperature_c = -56.5; // Constant temperature in Celsius for this zone
-            let pressure_kpa = 100.0; // Pressure too high for the Lower Stratosphere
+        fn test_calculate_altitude_isa_offset_closed_form() {
+            // Test against the closed-form integral of the troposphere and the isothermal layer above it
+            let t0 = AltitudeCalculator::SEA_LEVEL_TEMPERATURE_C + KELVIN_OFFSET;
+            let lapse = AltitudeCalculator::LAPSE_RATE;
+            let t_strat = AltitudeCalculator::STRATOPAUSE_TEMPERATURE_C + KELVIN_OFFSET;
     
-            let altitude = calculate_altitude(zone, temperature_c, pressure_kpa);
+            let pressure_altitude = altitude_from_pressure(pressure_at_altitude(5000.0)).unwrap();
+            let expected = pressure_altitude + 15.0 / lapse * (t0 / (t0 - lapse * pressure_altitude)).ln();
+            let altitude = calculate_altitude_isa_offset(pressure_at_altitude(5000.0), 15.0).unwrap();
+            assert!((altitude - expected).abs() < 1e-6);
+    
+            let pressure_altitude = altitude_from_pressure(pressure_at_altitude(20000.0)).unwrap();
+            let troposphere = 15.0 / lapse * (t0 / (t0 - lapse * 11000.0)).ln();
+            let expected = pressure_altitude + troposphere + 15.0 * (pressure_altitude - 11000.0) / t_strat;
+            let altitude = calculate_altitude_isa_offset(pressure_at_altitude(20000.0), 15.0).unwrap();
+            assert!((altitude - expected).abs() < 1e-6);
+    
+            // Below sea level a warm day lowers the altitude further
+            let below = altitude_from_pressure(103.0).unwrap();
+            assert!(calculate_altitude_isa_offset(103.0, 15.0).unwrap() < below);
+        }
+    
+        #[test]
+        fn test_troposphere_top_follows_reference() {
+            // The default troposphere ends at the standard tropopause pressure
+            let zone = AtmosphereZone::Troposphere;
+            match calculate_altitude(zone, -50.0, 22.0) {
+                Err(AltitudeError::PressureTooLow { limit_kpa }) => {
+                    assert!((limit_kpa - AltitudeCalculator::TROPOPAUSE_PRESSURE_KPA).abs() < 1e-9)
+                }
+                result => panic!("unexpected {:?}", result),
+            }
     
-            // Ensure the function returns None for invalid input
-            assert!(altitude.is_none());
+            // A higher reference raises the pressure at 11 km, so nothing above it is returned
+            let high = AltitudeCalculator::builder().sea_level_pressure(103.0).build().unwrap();
+            let top = high.pressure_at_altitude(11000.0);
+            assert!(matches!(high.calculate_altitude(zone, -50.0, 22.8), Err(AltitudeError::PressureTooLow { .. })));
+            assert!(high.calculate_altitude(zone, -50.0, top * (1.0 + 1e-9)).unwrap() < 11000.0);
+    
+            let clamping = AltitudeCalculator::builder().sea_level_pressure(103.0).bounds_policy(BoundsPolicy::Clamp);
+            assert_eq!(clamping.build().unwrap().calculate_altitude(zone, -50.0, 22.8), Ok(11000.0));
         }
     }    
-
-fn main() {
-    let zone = AtmosphereZone::Troposphere; // Determined in advance
-    let temperature_c = 10.0; // Temperature in Celsius
-    let pressure_kpa = 90.0; // Pressure in kPa
-
-    match calculate_altitude(zone, temperature_c, pressure_kpa) {
-        Some(altitude) => println!("Altitude: {:.2} m", altitude),
-        None => println!("Invalid input for the given zone."),
-    }
-}
\ No newline at end of file
@@ -0,0 +1,12 @@
+use altitude_calculator::{calculate_altitude, AtmosphereZone};
+
+fn main() {
+    let zone = AtmosphereZone::Troposphere; // Determined in advance
+    let temperature_c = 10.0; // Temperature in Celsius
+    let pressure_kpa = 90.0; // Pressure in kPa
+
+    match calculate_altitude(zone, temperature_c, pressure_kpa) {
+        Ok(altitude) => println!("Altitude: {:.2} m", altitude),
+        Err(error) => println!("Invalid input for the given zone: {}", error),
+    }
+}
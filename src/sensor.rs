@@ -0,0 +1,107 @@
+use crate::{altitude_from_pressure, AltitudeError};
+
+/// Pressure and temperature sensor the altitude can be read from.
+///
+/// Implement this for a driver to use it with [`read_altitude`].
+pub trait Barometer {
+    /// Read the pressure in kPa.
+    fn pressure_kpa(&mut self) -> f64;
+
+    /// Read the temperature in Celsius.
+    fn temperature_c(&mut self) -> f64;
+}
+
+/// Read a sensor and calculate its altitude.
+///
+/// The altitude comes from the pressure alone, as with [`altitude_from_pressure`].
+/// The temperature is read as well so a faulty sensor is caught.
+///
+/// # Parameters
+/// - 'sensor': Barometer to read.
+///
+/// # Returns
+/// Geopotential altitude in meters, or the reason it could not be calculated.
+pub fn read_altitude<B: Barometer>(sensor: &mut B) -> Result<f64, AltitudeError> {
+    let pressure_kpa = sensor.pressure_kpa();
+    let temperature_c = sensor.temperature_c();
+
+    if !temperature_c.is_finite() {
+        return Err(AltitudeError::InvalidInput);
+    }
+
+    altitude_from_pressure(pressure_kpa)
+}
+
+/// Calculate altitude from the raw count of an analog barometer.
+///
+/// The count is converted to pressure with a linear calibration, then to
+/// altitude as with [`altitude_from_pressure`].
+///
+/// # Parameters
+/// - 'count': Raw ADC count.
+/// - 'slope_kpa_per_count': Pressure change per count in kPa.
+/// - 'offset_kpa': Pressure at a count of zero in kPa.
+///
+/// # Returns
+/// Geopotential altitude in meters, or the reason it could not be calculated.
+pub fn altitude_from_adc(count: u16, slope_kpa_per_count: f64, offset_kpa: f64) -> Result<f64, AltitudeError> {
+    altitude_from_pressure(offset_kpa + slope_kpa_per_count * f64::from(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pressure_at_altitude;
+
+    /// Barometer returning fixed readings.
+    struct MockBarometer {
+        pressure_kpa: f64,
+        temperature_c: f64,
+        reads: usize,
+    }
+
+    impl Barometer for MockBarometer {
+        fn pressure_kpa(&mut self) -> f64 {
+            self.reads += 1;
+            self.pressure_kpa
+        }
+
+        fn temperature_c(&mut self) -> f64 {
+            self.reads += 1;
+            self.temperature_c
+        }
+    }
+
+    #[test]
+    fn test_read_altitude() {
+        // Test reading a sensor at 2000 m
+        let mut sensor = MockBarometer {
+            pressure_kpa: pressure_at_altitude(2000.0),
+            temperature_c: 2.0,
+            reads: 0,
+        };
+
+        assert!((read_altitude(&mut sensor).unwrap() - 2000.0).abs() < 1e-6);
+        assert_eq!(sensor.reads, 2);
+
+        // A failed reading is reported instead of producing an altitude
+        sensor.pressure_kpa = f64::NAN;
+        assert_eq!(read_altitude(&mut sensor), Err(AltitudeError::InvalidInput));
+
+        sensor.pressure_kpa = 80.0;
+        sensor.temperature_c = f64::NAN;
+        assert_eq!(read_altitude(&mut sensor), Err(AltitudeError::InvalidInput));
+    }
+
+    #[test]
+    fn test_altitude_from_adc() {
+        // A 12-bit converter spanning 15 to 115 kPa
+        let slope = 100.0 / 4095.0;
+        let count = ((pressure_at_altitude(1500.0) - 15.0) / slope).round() as u16;
+        assert!((altitude_from_adc(count, slope, 15.0).unwrap() - 1500.0).abs() < 3.0);
+
+        // Full scale is above the model's highest pressure
+        assert!(altitude_from_adc(4095, slope, 15.0).is_err());
+        assert_eq!(altitude_from_adc(0, f64::NAN, 15.0), Err(AltitudeError::InvalidInput));
+    }
+}
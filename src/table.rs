@@ -0,0 +1,170 @@
+use crate::{determine_zone, AtmosphereProperties};
+
+/// Altitude between the rows of [`STANDARD_ATMOSPHERE_TABLE`], in meters.
+const TABLE_STEP_M: f64 = 500.0;
+
+/// The NASA model every 500 m from 0 m to 30 000 m, as geopotential altitude in
+/// m, pressure in kPa, temperature in Celsius and density in kg/m³.
+pub const STANDARD_ATMOSPHERE_TABLE: [(f64, f64, f64, f64); 61] = [
+    (0.0, 101.32500, 15.040, 1.224808),
+    (500.0, 95.47030, 11.795, 1.167179),
+    (1000.0, 89.89260, 8.550, 1.111648),
+    (1500.0, 84.58175, 5.305, 1.058162),
+    (2000.0, 79.52787, 2.060, 1.006666),
+    (2500.0, 74.72135, -1.185, 0.957110),
+    (3000.0, 70.15280, -4.430, 0.909443),
+    (3500.0, 65.81312, -7.675, 0.863613),
+    (4000.0, 61.69342, -10.920, 0.819571),
+    (4500.0, 57.78507, -14.165, 0.777269),
+    (5000.0, 54.07967, -17.410, 0.736658),
+    (5500.0, 50.56906, -20.655, 0.697690),
+    (6000.0, 47.24531, -23.900, 0.660319),
+    (6500.0, 44.10071, -27.145, 0.624499),
+    (7000.0, 41.12778, -30.390, 0.590185),
+    (7500.0, 38.31925, -33.635, 0.557333),
+    (8000.0, 35.66808, -36.880, 0.525898),
+    (8500.0, 33.16744, -40.125, 0.495838),
+    (9000.0, 30.81068, -43.370, 0.467110),
+    (9500.0, 28.59140, -46.615, 0.439674),
+    (10000.0, 26.50337, -49.860, 0.413487),
+    (10500.0, 24.54058, -53.105, 0.388511),
+    (11000.0, 22.69718, -56.350, 0.364706),
+    (11500.0, 20.98359, -56.460, 0.337343),
+    (12000.0, 19.39937, -56.460, 0.311874),
+    (12500.0, 17.93476, -56.460, 0.288328),
+    (13000.0, 16.58072, -56.460, 0.266560),
+    (13500.0, 15.32891, -56.460, 0.246435),
+    (14000.0, 14.17161, -56.460, 0.227830),
+    (14500.0, 13.10168, -56.460, 0.210629),
+    (15000.0, 12.11253, -56.460, 0.194727),
+    (15500.0, 11.19806, -56.460, 0.180026),
+    (16000.0, 10.35263, -56.460, 0.166434),
+    (16500.0, 9.57103, -56.460, 0.153869),
+    (17000.0, 8.84843, -56.460, 0.142252),
+    (17500.0, 8.18040, -56.460, 0.131512),
+    (18000.0, 7.56279, -56.460, 0.121583),
+    (18500.0, 6.99182, -56.460, 0.112404),
+    (19000.0, 6.46395, -56.460, 0.103918),
+    (19500.0, 5.97593, -56.460, 0.096072),
+    (20000.0, 5.52476, -56.460, 0.088819),
+    (20500.0, 5.10766, -56.460, 0.082113),
+    (21000.0, 4.72204, -56.460, 0.075914),
+    (21500.0, 4.36553, -56.460, 0.070183),
+    (22000.0, 4.03595, -56.460, 0.064884),
+    (22500.0, 3.73124, -56.460, 0.059985),
+    (23000.0, 3.44954, -56.460, 0.055457),
+    (23500.0, 3.18911, -56.460, 0.051270),
+    (24000.0, 2.94833, -56.460, 0.047399),
+    (24500.0, 2.72574, -56.460, 0.043820),
+    (25000.0, 2.51995, -56.460, 0.040512),
+    (25500.0, 2.33017, -54.965, 0.037204),
+    (26000.0, 2.15583, -53.470, 0.034187),
+    (26500.0, 1.99559, -51.975, 0.031432),
+    (27000.0, 1.84822, -50.480, 0.028915),
+    (27500.0, 1.71261, -48.985, 0.026615),
+    (28000.0, 1.58776, -47.490, 0.024511),
+    (28500.0, 1.47274, -45.995, 0.022586),
+    (29000.0, 1.36673, -44.500, 0.020823),
+    (29500.0, 1.26897, -43.005, 0.019208),
+    (30000.0, 1.17877, -41.510, 0.017727),
+];
+
+/// Look up the model atmosphere at an altitude from the precomputed table.
+///
+/// Interpolates linearly between the rows of [`STANDARD_ATMOSPHERE_TABLE`], so no
+/// `powf` or `exp` is evaluated. Pressure and density are within 0.1 % of the
+/// model. Temperatures are exact, as the zone boundaries fall on rows, apart from
+/// the 0.11 K step of the model at the tropopause.
+///
+/// # Parameters
+/// - 'altitude_m': Geopotential altitude in meters, limited to the 0 m to 30 000 m of the table.
+///
+/// # Returns
+/// Zone, temperature, pressure and density at the altitude.
+pub fn lookup_interpolated(altitude_m: f64) -> AtmosphereProperties {
+    let last = STANDARD_ATMOSPHERE_TABLE.len() - 1;
+    let altitude_m = altitude_m.clamp(0.0, STANDARD_ATMOSPHERE_TABLE[last].0);
+
+    // Casting truncates towards the row below, and the top row interpolates from the one before it
+    let row = ((altitude_m / TABLE_STEP_M) as usize).min(last - 1);
+    let (base_m, p0, t0, rho0) = STANDARD_ATMOSPHERE_TABLE[row];
+    let (_, p1, t1, rho1) = STANDARD_ATMOSPHERE_TABLE[row + 1];
+    let fraction = (altitude_m - base_m) / TABLE_STEP_M;
+
+    AtmosphereProperties {
+        zone: determine_zone(altitude_m),
+        temperature_c: t0 + (t1 - t0) * fraction,
+        pressure_kpa: p0 + (p1 - p0) * fraction,
+        density_kgm3: rho0 + (rho1 - rho0) * fraction,
+    }
+}
+
+/// Look up the altitude of a pressure from the precomputed table.
+///
+/// Binary searches the pressures of [`STANDARD_ATMOSPHERE_TABLE`] and interpolates
+/// linearly between the rows, so no `ln` or `powf` is evaluated. The result is
+/// within 6 m of `altitude_from_pressure`.
+///
+/// # Parameters
+/// - 'pressure_kpa': Pressure in kPa, limited to the pressures of the table.
+///
+/// # Returns
+/// Geopotential altitude in meters between 0 m and 30 000 m, or NaN if the pressure is NaN.
+pub fn lookup_altitude_from_pressure(pressure_kpa: f64) -> f64 {
+    if pressure_kpa.is_nan() {
+        return f64::NAN;
+    }
+
+    // Pressures fall down the table, so find the first row at or below the pressure
+    let last = STANDARD_ATMOSPHERE_TABLE.len() - 1;
+    let row = STANDARD_ATMOSPHERE_TABLE
+        .partition_point(|row| row.1 > pressure_kpa)
+        .clamp(1, last);
+    let (h0, p0, _, _) = STANDARD_ATMOSPHERE_TABLE[row - 1];
+    let (h1, p1, _, _) = STANDARD_ATMOSPHERE_TABLE[row];
+    let fraction = ((p0 - pressure_kpa) / (p0 - p1)).clamp(0.0, 1.0);
+
+    h0 + (h1 - h0) * fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{altitude_from_pressure, properties_at_altitude};
+
+    #[test]
+    fn test_lookup_interpolated() {
+        // Test between and on the rows against the analytic model
+        for step in 0..=600 {
+            let altitude_m = 50.0 * f64::from(step);
+            let table = lookup_interpolated(altitude_m);
+            let model = properties_at_altitude(altitude_m);
+
+            assert_eq!(table.zone, model.zone);
+            assert!((table.temperature_c - model.temperature_c).abs() < 0.12);
+            assert!((table.pressure_kpa / model.pressure_kpa - 1.0).abs() < 1e-3);
+            assert!((table.density_kgm3 / model.density_kgm3 - 1.0).abs() < 1e-3);
+        }
+
+        // Altitudes beyond the table take its last row
+        assert_eq!(lookup_interpolated(40000.0), lookup_interpolated(30000.0));
+    }
+
+    #[test]
+    fn test_lookup_altitude_from_pressure() {
+        // Test the table inverse against the analytic inverse
+        let mut worst: f64 = 0.0;
+        for step in 0..=600 {
+            let pressure_kpa = crate::pressure_at_altitude(50.0 * f64::from(step));
+            let exact = altitude_from_pressure(pressure_kpa).unwrap();
+
+            worst = worst.max((lookup_altitude_from_pressure(pressure_kpa) - exact).abs());
+        }
+        assert!(worst < 6.0);
+
+        // Pressures beyond the table stop at its ends
+        assert_eq!(lookup_altitude_from_pressure(110.0), 0.0);
+        assert_eq!(lookup_altitude_from_pressure(0.5), 30000.0);
+        assert!(lookup_altitude_from_pressure(f64::NAN).is_nan());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{FEET_PER_METER, KELVIN_OFFSET, METERS_PER_NAUTICAL_MILE};
+
+/// Units a pressure reading can be given in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PressureUnit {
+    /// Pascal.
+    Pa,
+    /// Hectopascal.
+    HPa,
+    /// Kilopascal.
+    KPa,
+    /// Millibar, equal to one hectopascal.
+    Mbar,
+    /// Inches of mercury, as used by American altimeters.
+    InHg,
+    /// Millimeters of mercury.
+    MmHg,
+    /// Torr, defined as 1/760 of a standard atmosphere.
+    Torr,
+    /// Pounds per square inch.
+    Psi,
+    /// Standard atmosphere.
+    Atm,
+}
+
+/// Convert a pressure to kPa.
+///
+/// # Parameters
+/// - 'value': Pressure in the given unit.
+/// - 'unit': Unit of the pressure.
+///
+/// # Returns
+/// Pressure in kPa.
+pub fn to_kpa(value: f64, unit: PressureUnit) -> f64 {
+    match unit {
+        PressureUnit::Pa => value / 1000.0,
+        PressureUnit::HPa | PressureUnit::Mbar => value / 10.0,
+        PressureUnit::KPa => value,
+        PressureUnit::InHg => value * 3.386389,
+        PressureUnit::MmHg => value * 0.1333224,
+        PressureUnit::Torr => value * 101.325 / 760.0,
+        PressureUnit::Psi => value * 6.894757,
+        PressureUnit::Atm => value * 101.325,
+    }
+}
+
+/// Units a temperature reading can be given in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureUnit {
+    /// Degrees Celsius.
+    Celsius,
+    /// Kelvin.
+    Kelvin,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+    /// Degrees Rankine, an absolute scale with Fahrenheit-sized degrees.
+    Rankine,
+}
+
+/// Convert a temperature to Celsius.
+///
+/// # Parameters
+/// - 'value': Temperature in the given unit.
+/// - 'unit': Unit of the temperature.
+///
+/// # Returns
+/// Temperature in Celsius.
+pub fn to_celsius(value: f64, unit: TemperatureUnit) -> f64 {
+    match unit {
+        TemperatureUnit::Celsius => value,
+        TemperatureUnit::Kelvin => value - KELVIN_OFFSET,
+        TemperatureUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+        TemperatureUnit::Rankine => (value - 491.67) * 5.0 / 9.0,
+    }
+}
+
+/// Units a calculated altitude can be given in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AltitudeUnit {
+    /// Meters.
+    Meters,
+    /// Feet.
+    Feet,
+    /// Kilometers.
+    Kilometers,
+    /// International nautical miles.
+    NauticalMiles,
+}
+
+impl AltitudeUnit {
+    /// Number of this unit in one meter.
+    pub const fn per_meter(self) -> f64 {
+        match self {
+            AltitudeUnit::Meters => 1.0,
+            AltitudeUnit::Feet => FEET_PER_METER,
+            AltitudeUnit::Kilometers => 0.001,
+            AltitudeUnit::NauticalMiles => 1.0 / METERS_PER_NAUTICAL_MILE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_kpa() {
+        // Test conversion of common altimeter settings to kPa
+        assert!((to_kpa(29.92, PressureUnit::InHg) - 101.32).abs() < 0.01);
+        assert!((to_kpa(1013.25, PressureUnit::HPa) - 101.325).abs() < 1e-9);
+        assert!((to_kpa(1013.25, PressureUnit::Mbar) - 101.325).abs() < 1e-9);
+        assert!((to_kpa(101325.0, PressureUnit::Pa) - 101.325).abs() < 1e-9);
+        assert!((to_kpa(1.0, PressureUnit::Atm) - 101.325).abs() < 1e-9);
+        assert!((to_kpa(760.0, PressureUnit::MmHg) - 101.325).abs() < 0.01);
+        assert!((to_kpa(760.0, PressureUnit::Torr) - 101.325).abs() < 1e-9);
+        assert!((to_kpa(14.696, PressureUnit::Psi) - 101.325).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_to_celsius() {
+        // Test conversion of Fahrenheit and Kelvin readings to Celsius
+        assert!((to_celsius(32.0, TemperatureUnit::Fahrenheit) - 0.0).abs() < 1e-9);
+        assert!((to_celsius(288.15, TemperatureUnit::Kelvin) - 15.0).abs() < 1e-9);
+        assert!((to_celsius(15.0, TemperatureUnit::Celsius) - 15.0).abs() < 1e-9);
+        assert!((to_celsius(491.67, TemperatureUnit::Rankine) - 0.0).abs() < 1e-9);
+        assert!((to_celsius(518.67, TemperatureUnit::Rankine) - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_altitude_unit() {
+        // Test the length of one of each unit in meters
+        assert_eq!(AltitudeUnit::Meters.per_meter(), 1.0);
+        assert!((1.0 / AltitudeUnit::Feet.per_meter() - 0.3048).abs() < 1e-9);
+        assert!((1.0 / AltitudeUnit::Kilometers.per_meter() - 1000.0).abs() < 1e-9);
+        assert!((1.0 / AltitudeUnit::NauticalMiles.per_meter() - 1852.0).abs() < 1e-9);
+    }
+}